@@ -0,0 +1,558 @@
+//! Critical apparatus of witnesses against a base text.
+//!
+//! The base text and the witnesses are collated on the core text of their
+//! words, as computed by [`core_char`](crate::core_char). Each run of
+//! units where they do not all read the same is an entry, given in the
+//! usual layout `lemma] witnesses; variant witnesses`, where the lemma is
+//! the reading of the base text. A [`Layout::Positive`] apparatus lists
+//! the witnesses of the lemma, a [`Layout::Negative`] one only the ones of
+//! the variants.
+//! # Example :
+//! ```
+//! use gnt_tools::apparatus::{apparatus, Layout};
+//!
+//! let apparatus = apparatus("εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς λέγων", &[
+//!     ("A", "εἶπεν δὲ παραβολὴν πρὸς αὐτούς λέγων"),
+//!     ("B", "εἶπεν δὲ καὶ παραβολὴν αὐτοῖς λέγων"),
+//!     ("C", "ἔλεγεν δὲ παραβολὴν πρὸς αὐτούς"),
+//! ]).unwrap();
+//!
+//! assert_eq!(apparatus.to_text(Layout::Positive),
+//!            "εἶπεν] A B; ἔλεγεν C\n\
+//!             δὲ] A C; δὲ καὶ B\n\
+//!             πρὸς αὐτοὺς] A C; αὐτοῖς B\n\
+//!             λέγων] A B; om. C\n");
+//! assert_eq!(apparatus.to_text(Layout::Negative),
+//!            "εἶπεν] ἔλεγεν C\n\
+//!             δὲ] δὲ καὶ B\n\
+//!             πρὸς αὐτοὺς] αὐτοῖς B\n\
+//!             λέγων] om. C\n");
+//! ```
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::collation::{collate_words, Collation, Token};
+use crate::diff::{words, Word};
+use crate::osis::escape;
+use crate::reference::Reference;
+use crate::UnhandledGreekChar;
+
+/// The kind of an apparatus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// The witnesses of the lemma are listed before the variants.
+    Positive,
+    /// Only the witnesses of the variants are listed.
+    Negative,
+}
+
+/// A reading of an entry, with its witnesses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reading {
+    /// The words of the reading, as found in its first witness. Empty for
+    /// an omission.
+    pub text: String,
+    /// The sigla of the witnesses of the reading.
+    pub sigla: Vec<String>,
+}
+
+/// An entry of the apparatus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The verse of the entry, if known.
+    pub reference: Option<Reference>,
+    /// The words of the base text in the entry.
+    pub words: Range<usize>,
+    /// The reading of the base text, and the witnesses agreeing with it.
+    /// Its text is empty only when the base text has no words in the
+    /// verse of the entry.
+    pub lemma: Reading,
+    /// The other readings, in the order of their first witness.
+    pub readings: Vec<Reading>,
+}
+
+/// A critical apparatus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Apparatus {
+    /// The sigla of the witnesses, without the base text.
+    pub sigla: Vec<String>,
+    /// The words of the base text.
+    pub base: Vec<Token>,
+    /// The entries, in the order of the base text.
+    pub entries: Vec<Entry>,
+}
+
+/// The apparatus of the `witnesses`, given by their siglum and their text,
+/// against the text `base`.
+pub fn apparatus(base: &str, witnesses: &[(&str, &str)])
+    -> Result<Apparatus, UnhandledGreekChar> {
+
+    let base = words(base)?;
+    let words = witnesses.iter()
+                         .map(|(siglum, text)| Ok((*siglum, words(text)?)))
+                         .collect::<Result<Vec<_>, UnhandledGreekChar>>()?;
+    let words : Vec<_> = words.iter()
+                              .map(|(siglum, words)| (*siglum, words.as_slice()))
+                              .collect();
+
+    Ok(apparatus_words(&base, &words))
+}
+
+/// The apparatus of the `witnesses`, given by their siglum and their
+/// words, against the words `base`. The base text is collated first, so
+/// that the other witnesses are aligned against it.
+pub fn apparatus_words(base: &[Word], witnesses: &[(&str, &[Word])]) -> Apparatus {
+    let all : Vec<(&str, &[Word])> = std::iter::once(("", base))
+                                         .chain(witnesses.iter().copied())
+                                         .collect();
+
+    from_collation(&collate_words(&all), 0)
+}
+
+/// The apparatus of the witnesses of `collation` against the witness at
+/// index `base`, which is left out of the sigla.
+///
+/// An entry is a run of variant units of a single verse, where the same
+/// witnesses differ from the base text. An entry where
+/// the base text has no words is widened to the word of the base text
+/// before it in its verse, or else to the one after it. Its lemma is
+/// empty only when the verse has no words in the base text : it is then
+/// written as an empty `\edtext{}` in LaTeX and an empty `<lem/>` in TEI.
+pub fn from_collation(collation: &Collation, base: usize) -> Apparatus {
+    let units = &collation.units;
+    let reference = |u: usize| {
+        units[u][base].as_ref()
+                      .or_else(|| units[u].iter().flatten().next())
+                      .and_then(|t| t.reference)
+    };
+    let has_base = |range: &Range<usize>| units[range.clone()].iter()
+                                                              .any(|u| u[base].is_some());
+    // Which witnesses agree with the base text at a unit.
+    let agreement = |u: usize| -> Vec<bool> {
+        let core = |w: usize| units[u][w].as_ref().map(|t| &t.core);
+        (0..collation.sigla.len()).map(|w| core(w) == core(base)).collect()
+    };
+
+    // The runs of variant units with the same witnesses against the base.
+    let mut runs : Vec<Range<usize>> = Vec::new();
+    for u in 0..units.len() {
+        if !collation.is_variant(u) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some(run) if run.end == u && reference(run.start) == reference(u)
+                         && agreement(run.start) == agreement(u) => {
+                run.end = u + 1;
+            }
+            _ => runs.push(u..u + 1),
+        }
+    }
+
+    // Widen the runs without a lemma, and merge the ones overlapping.
+    let mut ranges : Vec<Range<usize>> = Vec::new();
+    for mut run in runs {
+        if !has_base(&run) {
+            if run.start > 0 && reference(run.start - 1) == reference(run.start) {
+                run.start -= 1;
+            } else if run.end < units.len()
+                      && reference(run.end) == reference(run.start) {
+                run.end += 1;
+            }
+        }
+        match ranges.last_mut() {
+            Some(last) if last.end > run.start => last.end = last.end.max(run.end),
+            _ => ranges.push(run),
+        }
+    }
+
+    // The index of the first base word of each unit, and after the last.
+    let mut first_word = Vec::with_capacity(units.len() + 1);
+    let mut count = 0;
+    for unit in units {
+        first_word.push(count);
+        count += usize::from(unit[base].is_some());
+    }
+    first_word.push(count);
+
+    let reading = |range: &Range<usize>, w: usize| -> (String, String) {
+        let tokens : Vec<&Token> = units[range.clone()].iter()
+                                                       .filter_map(|u| u[w].as_ref())
+                                                       .collect();
+        let text : Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        let core : Vec<&str> = tokens.iter().map(|t| t.core.as_str()).collect();
+        (text.join(" "), core.join(" "))
+    };
+
+    let entries = ranges.into_iter().map(|range| {
+        let (text, core) = reading(&range, base);
+        let mut lemma = Reading { text, sigla: Vec::new() };
+        // The readings, with their core text.
+        let mut readings : Vec<(String, Reading)> = Vec::new();
+
+        for (w, siglum) in collation.sigla.iter().enumerate() {
+            if w == base {
+                continue;
+            }
+            let (text, other) = reading(&range, w);
+            if other == core {
+                lemma.sigla.push(siglum.clone());
+                continue;
+            }
+            match readings.iter_mut().find(|(c, _)| *c == other) {
+                Some((_, r)) => r.sigla.push(siglum.clone()),
+                None => readings.push((other, Reading {
+                    text,
+                    sigla: vec![siglum.clone()],
+                })),
+            }
+        }
+
+        Entry {
+            reference: reference(range.start),
+            words: first_word[range.start]..first_word[range.end],
+            lemma,
+            readings: readings.into_iter().map(|(_, r)| r).collect(),
+        }
+    }).collect();
+
+    Apparatus {
+        sigla: collation.sigla
+                        .iter()
+                        .enumerate()
+                        .filter(|(w, _)| *w != base)
+                        .map(|(_, s)| s.clone())
+                        .collect(),
+        base: units.iter().filter_map(|u| u[base].clone()).collect(),
+        entries,
+    }
+}
+
+impl Entry {
+    /// The readings of the entry after the lemma, as `(text, sigla)`
+    /// pairs, the lemma itself coming first with no text in a positive
+    /// apparatus.
+    fn parts(&self, layout: Layout) -> Vec<(Option<&str>, &[String])> {
+        let lemma = match layout {
+            Layout::Positive if !self.lemma.sigla.is_empty() => {
+                Some((None, self.lemma.sigla.as_slice()))
+            }
+            _ => None,
+        };
+
+        lemma.into_iter()
+             .chain(self.readings
+                        .iter()
+                        .map(|r| (Some(r.text.as_str()), r.sigla.as_slice())))
+             .collect()
+    }
+
+    /// The entry, without its lemma, with the texts, the omissions and the
+    /// sigla written by `text`, `omission` and `sigla`.
+    fn body(&self, layout: Layout,
+            text: impl Fn(&str) -> String,
+            omission: &str,
+            sigla: impl Fn(&[String]) -> String) -> String {
+
+        let parts : Vec<String> =
+            self.parts(layout)
+                .into_iter()
+                .map(|(reading, witnesses)| match reading {
+                    None => sigla(witnesses),
+                    Some("") => format!("{omission} {}", sigla(witnesses)),
+                    Some(reading) => format!("{} {}", text(reading), sigla(witnesses)),
+                })
+                .collect();
+
+        parts.join("; ")
+    }
+}
+
+impl Apparatus {
+    /// The apparatus as plain text, with a line per entry, starting with
+    /// its verse when known.
+    pub fn to_text(&self, layout: Layout) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            if let Some(r) = entry.reference {
+                write!(out, "{r} ").unwrap();
+            }
+            let body = entry.body(layout, str::to_string, "om.", |s| s.join(" "));
+            writeln!(out, "{}] {body}", entry.lemma.text).unwrap();
+        }
+
+        out
+    }
+
+    /// The apparatus as a Markdown list, with an item per entry, starting
+    /// with its verse in bold when known. The omissions are in italics.
+    pub fn to_markdown(&self, layout: Layout) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            out.push_str("- ");
+            if let Some(r) = entry.reference {
+                write!(out, "**{r}** ").unwrap();
+            }
+            let body = entry.body(layout, markdown, "*om.*",
+                                  |s| markdown(&s.join(" ")));
+            writeln!(out, "{}\\] {body}", markdown(&entry.lemma.text)).unwrap();
+        }
+
+        out
+    }
+
+    /// The base text as a numbered section of reledmac, with each entry
+    /// as an `\edtext` and its readings in an `\Afootnote`. The verses are
+    /// given in side notes.
+    pub fn to_latex(&self, layout: Layout) -> String {
+        let mut out = String::from("\\beginnumbering\n\\pstart\n");
+        let mut words : Vec<String> = Vec::new();
+        let mut verse = None;
+        let mut entries = self.entries.iter().peekable();
+        let mut w = 0;
+
+        loop {
+            let entry = entries.next_if(|e| e.words.start == w);
+            let reference = entry.and_then(|e| e.reference)
+                                 .or_else(|| self.base.get(w).and_then(|t| t.reference));
+            if reference.is_some() && reference != verse {
+                if let Some(r) = reference {
+                    words.push(format!("\\ledsidenote{{{r}}}"));
+                }
+                verse = reference;
+            }
+
+            match entry {
+                Some(entry) => {
+                    let body = entry.body(layout, latex, "\\textit{om.}",
+                                          |s| latex(&s.join(" ")));
+                    words.push(format!("\\edtext{{{}}}{{\\Afootnote{{{body}}}}}",
+                                       latex(&entry.lemma.text)));
+                    w = entry.words.end;
+                }
+                None if w < self.base.len() => {
+                    words.push(latex(&self.base[w].text));
+                    w += 1;
+                }
+                None => break,
+            }
+        }
+        out.push_str(&words.join(" "));
+        out.push_str("\n\\pend\n\\endnumbering\n");
+
+        out
+    }
+
+    /// The base text in a TEI `<ab>`, with each entry as an `<app>` of
+    /// parallel segmentation, its `n` giving its verse when known. It is
+    /// preceded by a `<listWit>` giving an `xml:id` to each witness, which
+    /// is its siglum when this is a valid XML name, e.g. `A` but not `33`,
+    /// and `wit1`, `wit2`, … otherwise. The `wit` of the readings points to
+    /// these identifiers.
+    pub fn to_tei(&self, layout: Layout) -> String {
+        let mut ids : Vec<String> = Vec::with_capacity(self.sigla.len());
+        for (i, siglum) in self.sigla.iter().enumerate() {
+            let mut id = if is_xml_name(siglum) {
+                siglum.clone()
+            } else {
+                format!("wit{}", i + 1)
+            };
+            while ids.contains(&id) {
+                id.push('_');
+            }
+            ids.push(id);
+        }
+        let wit = |sigla: &[String]| -> String {
+            let pointers : Vec<String> =
+                sigla.iter()
+                     .filter_map(|s| self.sigla.iter().position(|t| t == s))
+                     .map(|i| format!("#{}", escape(&ids[i])))
+                     .collect();
+            pointers.join(" ")
+        };
+        let element = |name: &str, text: &str, sigla: &[String]| -> String {
+            let attribute = if sigla.is_empty() {
+                String::new()
+            } else {
+                format!(" wit=\"{}\"", wit(sigla))
+            };
+            if text.is_empty() {
+                format!("<{name}{attribute}/>")
+            } else {
+                format!("<{name}{attribute}>{}</{name}>", escape(text))
+            }
+        };
+
+        let mut words : Vec<String> = Vec::new();
+        let mut entries = self.entries.iter().peekable();
+        let mut w = 0;
+
+        loop {
+            match entries.next_if(|e| e.words.start == w) {
+                Some(entry) => {
+                    let mut app = String::from("<app");
+                    if let Some(r) = entry.reference {
+                        write!(app, " n=\"{r}\"").unwrap();
+                    }
+                    let sigla : &[String] = match layout {
+                        Layout::Positive => &entry.lemma.sigla,
+                        Layout::Negative => &[],
+                    };
+                    write!(app, ">{}", element("lem", &entry.lemma.text, sigla)).unwrap();
+                    for r in &entry.readings {
+                        app.push_str(&element("rdg", &r.text, &r.sigla));
+                    }
+                    app.push_str("</app>");
+                    words.push(app);
+                    w = entry.words.end;
+                }
+                None if w < self.base.len() => {
+                    words.push(escape(&self.base[w].text));
+                    w += 1;
+                }
+                None => break,
+            }
+        }
+
+        let mut out = String::from("<listWit>\n");
+        for (id, siglum) in ids.iter().zip(&self.sigla) {
+            writeln!(out, "<witness xml:id=\"{}\">{}</witness>",
+                     escape(id), escape(siglum)).unwrap();
+        }
+        writeln!(out, "</listWit>\n<ab>{}</ab>", words.join(" ")).unwrap();
+
+        out
+    }
+}
+
+/// Whether `s` can be an `xml:id` : a letter or `_`, followed by letters,
+/// digits, `_`, `-` or `.`.
+fn is_xml_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+    && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// `s` with the characters of the Markdown syntax escaped.
+fn markdown(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '*' | '_' | '[' | ']' | '`' | '#' | '<' | '>' | '|') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// `s` with the special characters of LaTeX escaped.
+fn latex(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::book_words;
+    use crate::reference::Book;
+    use crate::verse::parse;
+
+    fn sample() -> Apparatus {
+        apparatus("ὁ δὲ εἶπεν αὐτοῖς", &[
+            ("א", "ὁ δὲ εἶπεν αὐτοῖς"),
+            ("B", "ὁ δὲ Ἰησοῦς εἶπεν αὐτοῖς"),
+            ("D", "ὁ δὲ εἶπεν"),
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_entries() {
+        let apparatus = sample();
+
+        assert_eq!(apparatus.base.len(), 4);
+        assert_eq!(apparatus.entries.len(), 2);
+        let entry = &apparatus.entries[0];
+        assert_eq!(entry.words, 1..2);
+        assert_eq!(entry.lemma, Reading {
+            text: "δὲ".to_string(),
+            sigla: vec!["א".to_string(), "D".to_string()],
+        });
+        assert_eq!(entry.readings, [Reading {
+            text: "δὲ Ἰησοῦς".to_string(),
+            sigla: vec!["B".to_string()],
+        }]);
+        assert_eq!(apparatus.entries[1].readings[0].text, "");
+    }
+
+    #[test]
+    fn test_addition() {
+        let text = |base, witness| {
+            apparatus(base, &[("B", witness)]).unwrap().to_text(Layout::Negative)
+        };
+
+        assert_eq!(text("εἶπεν δὲ αὐτοῖς", "εἶπεν καὶ ἔλεγεν αὐτοῖς"),
+                   "δὲ] καὶ ἔλεγεν B\n");
+        assert_eq!(text("εἶπεν αὐτοῖς", "εἶπεν δὲ καὶ αὐτοῖς"),
+                   "εἶπεν] εἶπεν δὲ καὶ B\n");
+    }
+
+    #[test]
+    fn test_references() {
+        let a = parse("16 Εἶπεν δὲ παραβολὴν 17 καὶ διελογίζετο");
+        let b = parse("16 Εἶπεν δὲ 17 καὶ ἐλογίζετο");
+        let a = book_words(Book::Luke, &a[0]).unwrap();
+        let b = book_words(Book::Luke, &b[0]).unwrap();
+
+        let apparatus = apparatus_words(&a, &[("B", &b)]);
+        assert_eq!(apparatus.to_text(Layout::Negative),
+                   "Luke 1:16 παραβολὴν] om. B\n\
+                    Luke 1:17 διελογίζετο] ἐλογίζετο B\n");
+        assert_eq!(apparatus.to_markdown(Layout::Positive),
+                   "- **Luke 1:16** παραβολὴν\\] *om.* B\n\
+                    - **Luke 1:17** διελογίζετο\\] ἐλογίζετο B\n");
+    }
+
+    #[test]
+    fn test_latex() {
+        assert_eq!(sample().to_latex(Layout::Positive),
+                   "\\beginnumbering\n\\pstart\n\
+                    ὁ \\edtext{δὲ}{\\Afootnote{א D; δὲ Ἰησοῦς B}} εἶπεν \
+                    \\edtext{αὐτοῖς}{\\Afootnote{א B; \\textit{om.} D}}\n\
+                    \\pend\n\\endnumbering\n");
+        assert_eq!(latex("100% & {x}"), "100\\% \\& \\{x\\}");
+    }
+
+    #[test]
+    fn test_tei() {
+        assert_eq!(sample().to_tei(Layout::Positive),
+                   "<listWit>\n\
+                    <witness xml:id=\"א\">א</witness>\n\
+                    <witness xml:id=\"B\">B</witness>\n\
+                    <witness xml:id=\"D\">D</witness>\n\
+                    </listWit>\n<ab>ὁ <app><lem wit=\"#א #D\">δὲ</lem>\
+                    <rdg wit=\"#B\">δὲ Ἰησοῦς</rdg></app> εἶπεν \
+                    <app><lem wit=\"#א #B\">αὐτοῖς</lem><rdg wit=\"#D\"/></app></ab>\n");
+        assert!(sample().to_tei(Layout::Negative).contains("<lem>δὲ</lem>"));
+
+        let tei = apparatus("εἶπεν δὲ", &[("33", "εἶπεν"), ("1739", "εἶπεν δὲ")])
+            .unwrap()
+            .to_tei(Layout::Positive);
+        assert!(tei.contains("<witness xml:id=\"wit1\">33</witness>"));
+        assert!(tei.contains("<lem wit=\"#wit2\">δὲ</lem><rdg wit=\"#wit1\"/>"));
+    }
+}
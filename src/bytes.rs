@@ -0,0 +1,216 @@
+use unicode_normalization::char::compose;
+
+use crate::{is_combining, CoreEngine, UnhandledGreekChar};
+
+/// Appends to `out` the core text of the UTF-8 bytes `input`, e.g. a
+/// memory-mapped file.
+///
+/// The ASCII characters (spaces, digits, punctuation, verse numbers) are
+/// always dropped, so their runs are skipped eight bytes at a time; only
+/// the other sequences are decoded. The invalid UTF-8 sequences are
+/// dropped, like any character outside the Greek blocks.
+///
+/// Stops at the first unhandled character and returns it, with its byte
+/// offset in `input`; `out` then holds the core text before it.
+/// # Example :
+/// ```
+/// use gnt_tools::core_text_bytes;
+///
+/// let mut out = Vec::new();
+/// core_text_bytes("16 Εἶπεν δὲ παραβολὴν".as_bytes(), &mut out).unwrap();
+///
+/// assert_eq!(out, "ειπενδεπαραβολην".as_bytes());
+/// ```
+pub fn core_text_bytes(input: &[u8], out: &mut Vec<u8>)
+    -> Result<(), UnhandledGreekChar> {
+    CoreEngine::default_ref().core_text_bytes(input, out)
+}
+
+impl CoreEngine {
+    /// Same as [`core_text_bytes`], following the profile of the engine.
+    ///
+    /// With [`CoreProfile::suspended_nu`](crate::CoreProfile::suspended_nu),
+    /// the combining macron needs to look at the end of the word, so each
+    /// valid UTF-8 run of the input goes through [`CoreEngine::core_text`]
+    /// instead. The offset of an unhandled character is still the one in
+    /// `input`.
+    pub fn core_text_bytes(&self, input: &[u8], out: &mut Vec<u8>)
+        -> Result<(), UnhandledGreekChar> {
+
+        if self.profile().is_suspended_nu() {
+            let mut offset = 0;
+            for chunk in input.utf8_chunks() {
+                let s = chunk.valid();
+                let (core, e) = match self.core_text(s) {
+                    Ok(core) => (core, None),
+                    Err(e) => (self.core_text(&s[..e.offset])?, Some(e)),
+                };
+                out.extend_from_slice(core.as_str().as_bytes());
+                if let Some(e) = e {
+                    return Err(UnhandledGreekChar { offset: offset + e.offset,
+                                                    ..e });
+                }
+                offset += s.len() + chunk.invalid().len();
+            }
+            return Ok(());
+        }
+
+        out.reserve(input.len() / 2);
+        let mut i = 0;
+        // The last letter written, and where it starts in `out`, while
+        // its marks may follow.
+        let mut last = None;
+
+        while i < input.len() {
+            let run = ascii_run(&input[i..]);
+            if run > 0 {
+                i += run;
+                last = None;
+            }
+            if i == input.len() {
+                break;
+            }
+
+            let (c, len) = decode(&input[i..]);
+            let Some(c) = c else {
+                i += len;
+                last = None;
+                continue;
+            };
+            last = last.filter(|_| is_combining(c));
+            if let Some((l, at)) = last.filter(|_| self.profile().keeps_mark(c)) {
+                if let Some(composed) = compose(l, c) {
+                    out.truncate(at);
+                    push_char(out, composed);
+                    last = Some((composed, at));
+                    i += len;
+                    continue;
+                }
+            }
+            match self.try_core_char(&c) {
+                Ok(Some(core_c)) => {
+                    last = Some((core_c, out.len()));
+                    push_char(out, core_c);
+                }
+                Ok(None) => (),
+                Err(e) => return Err(UnhandledGreekChar { offset: i, ..e }),
+            }
+            i += len;
+        }
+
+        Ok(())
+    }
+}
+
+#[inline]
+fn push_char(out: &mut Vec<u8>, c: char) {
+    let mut buf = [0; 4];
+    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+}
+
+/// The length of the run of ASCII bytes `bytes` starts with.
+#[inline]
+fn ascii_run(bytes: &[u8]) -> usize {
+    const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+
+    let mut n = 0;
+    for chunk in bytes.chunks_exact(8) {
+        let word = u64::from_ne_bytes(chunk.try_into().unwrap());
+        if word & HIGH_BITS != 0 {
+            break;
+        }
+        n += 8;
+    }
+
+    n + bytes[n..].iter().take_while(|b| b.is_ascii()).count()
+}
+
+/// The char encoded at the start of `bytes`, which does not start with an
+/// ASCII byte, and its length. Gives `None` and a length of 1 for an
+/// invalid sequence.
+#[inline]
+fn decode(bytes: &[u8]) -> (Option<char>, usize) {
+    let cont = |i: usize| bytes.get(i)
+                               .filter(|b| **b & 0xC0 == 0x80)
+                               .map(|b| (*b & 0x3F) as u32);
+
+    match bytes[0] {
+        // The Greek and Coptic block and the combining marks.
+        b @ 0xC2..=0xDF => match cont(1) {
+            Some(c1) => (char::from_u32((b as u32 & 0x1F) << 6 | c1), 2),
+            None => (None, 1),
+        },
+        // The Greek Extended block.
+        b @ 0xE0..=0xEF => match (cont(1), cont(2)) {
+            (Some(c1), Some(c2)) => {
+                let c = (b as u32 & 0x0F) << 12 | c1 << 6 | c2;
+                // Overlong encodings and surrogates are invalid.
+                match char::from_u32(c).filter(|_| c >= 0x800) {
+                    Some(c) => (Some(c), 3),
+                    None => (None, 1),
+                }
+            }
+            _ => (None, 1),
+        },
+        _ => match bytes.get(..4).map(std::str::from_utf8) {
+            Some(Ok(s)) => (s.chars().next(), 4),
+            _ => (None, 1),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core_text, CoreProfile};
+
+    #[test]
+    fn test_bytes_is_core_text() {
+        let s = "1 Βίβλος γενέσεως Ἰησοῦ Χριστοῦ υἱοῦ Δαυὶδ υἱοῦ Ἀβραάμ.\n\
+                 ΚΑΤΑ ΜΑΘΘΑΙΟΝ  2\n\
+                 16 Εἶπεν δὲ παραβολὴν πρὸς αὐτούς· ᾄσατε « ἀΐδιος » 𝔊 ϛʹ";
+        let mut out = Vec::new();
+
+        core_text_bytes(s.as_bytes(), &mut out).unwrap();
+        assert_eq!(out, core_text(s).unwrap().as_str().as_bytes());
+
+        let profile = CoreProfile::new().suspended_nu(true).keep_case(true);
+        let mut out = Vec::new();
+        profile.engine()
+               .core_text_bytes("τη\u{304} ὥρᾳ".as_bytes(), &mut out)
+               .unwrap();
+        assert_eq!(out, "τηνωρα".as_bytes());
+
+        // The kept marks of a decomposed text.
+        let s = "Ἠσαι\u{308}ου, ω\u{313}\u{345}δῇ, ι\u{301}\u{308}";
+        let profile = CoreProfile::new().keep_diaeresis(true)
+                                        .keep_iota_subscript(true);
+        let mut out = Vec::new();
+        profile.engine().core_text_bytes(s.as_bytes(), &mut out).unwrap();
+        assert_eq!(out, profile.core_text(s).unwrap().as_str().as_bytes());
+        assert_eq!(out, "ηϲαϊουῳδῃϊ".as_bytes());
+    }
+
+    #[test]
+    fn test_bytes_errors() {
+        let mut out = Vec::new();
+
+        // A lone continuation byte, a truncated sequence and a surrogate.
+        core_text_bytes(b"\xCE\xBB\x80\xCF \xED\xA0\x80\xCF\x8C", &mut out).unwrap();
+        assert_eq!(out, "λο".as_bytes());
+
+        out.clear();
+        let e = core_text_bytes("λόγος ϣ".as_bytes(), &mut out).unwrap_err();
+        assert_eq!(e.offset, 11);
+        assert_eq!(out, "λογοϲ".as_bytes());
+
+        // The offsets are the ones of the input, invalid bytes included.
+        let engine = CoreProfile::new().suspended_nu(true).engine();
+        let input = b"\xCE\xBB\xFF\xFF \xCE\xBB\xCF\x8C\xCE\xB3\xCF\x8C\xCC\x84 \xCF\xA3";
+        out.clear();
+        let e = engine.core_text_bytes(input, &mut out).unwrap_err();
+        assert_eq!(e.offset, 16);
+        assert_eq!(&input[e.offset..], "ϣ".as_bytes());
+        assert_eq!(out, "λλογον".as_bytes());
+    }
+}
@@ -0,0 +1,524 @@
+//! The collation of several witnesses at once, in the manner of CollateX.
+//!
+//! The words of the witnesses are compared by their core text and aligned
+//! into a table of units : a unit holds, for each witness, the word it
+//! reads there, or nothing. The words of a witness found at another place
+//! in the others are reported as [`Transposition`]s. The table can be
+//! written as text, JSON or as a variant graph in the Graphviz DOT
+//! language.
+//! # Example :
+//! ```
+//! use gnt_tools::collation::collate;
+//!
+//! let collation = collate(&[
+//!     ("A", "εἶπεν δὲ παραβολὴν πρὸς αὐτούς"),
+//!     ("B", "εἶπεν δὲ καὶ παραβολὴν αὐτοῖς"),
+//!     ("C", "ἔλεγεν δὲ παραβολὴν πρὸς αὐτούς"),
+//! ]).unwrap();
+//!
+//! assert_eq!(collation.to_table(),
+//!            "A εἶπεν  δὲ -   παραβολὴν πρὸς αὐτούς\n\
+//!             B εἶπεν  δὲ καὶ παραβολὴν -    αὐτοῖς\n\
+//!             C ἔλεγεν δὲ -   παραβολὴν πρὸς αὐτούς\n");
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::diff::{myers, myers_by, words, Edit, Word};
+use crate::reference::Reference;
+use crate::UnhandledGreekChar;
+
+/// A word of a witness, in a [`Collation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// The core text of the word.
+    pub core: String,
+    /// The word as found in the witness.
+    pub text: String,
+    /// The verse of the word, if known.
+    pub reference: Option<Reference>,
+}
+
+impl From<&Word<'_>> for Token {
+    fn from(word: &Word) -> Token {
+        Token {
+            core: word.core.clone(),
+            text: word.text.to_string(),
+            reference: word.reference,
+        }
+    }
+}
+
+/// Words of a witness found at another place in another witness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transposition {
+    /// The index of the witness.
+    pub witness: usize,
+    /// The units where the witness has the words.
+    pub units: Range<usize>,
+    /// The index of the other witness.
+    pub other: usize,
+    /// The units where the other witness has them.
+    pub other_units: Range<usize>,
+}
+
+/// The alignment of several witnesses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collation {
+    /// The sigla of the witnesses.
+    pub sigla: Vec<String>,
+    /// The units, in the order of the text. Each has a token, or nothing,
+    /// for each witness, in the order of `sigla`.
+    pub units: Vec<Vec<Option<Token>>>,
+    /// The transpositions found.
+    pub transpositions: Vec<Transposition>,
+}
+
+/// Collates the `witnesses`, given by their siglum and their text.
+pub fn collate(witnesses: &[(&str, &str)])
+    -> Result<Collation, UnhandledGreekChar> {
+
+    let words = witnesses.iter()
+                         .map(|(siglum, text)| Ok((*siglum, words(text)?)))
+                         .collect::<Result<Vec<_>, UnhandledGreekChar>>()?;
+    let words : Vec<_> = words.iter()
+                              .map(|(siglum, words)| (*siglum, words.as_slice()))
+                              .collect();
+
+    Ok(collate_words(&words))
+}
+
+/// Collates the `witnesses`, given by their siglum and their words.
+///
+/// The witnesses are aligned one after the other against the units found
+/// so far, a word matching a unit when one of the words of the unit has
+/// the same core text. The words between two matches are put in the units
+/// left empty by the witness there, as variants, and in new units when
+/// there are more of them.
+pub fn collate_words(witnesses: &[(&str, &[Word])]) -> Collation {
+    let n = witnesses.len();
+    let mut units : Vec<Vec<Option<Token>>> = Vec::new();
+
+    for (w, (_, words)) in witnesses.iter().enumerate() {
+        let edits = myers_by(units.len(), words.len(), |i, j| {
+            units[i].iter().flatten().any(|t| t.core == words[j].core)
+        });
+
+        let mut aligned = Vec::with_capacity(units.len() + words.len());
+        let mut old = units.into_iter();
+        // The units left empty and the words left out since the last
+        // match.
+        let mut empty : Vec<usize> = Vec::new();
+        let mut left : Vec<&Word> = Vec::new();
+        let mut next = 0;
+
+        let flush = |aligned: &mut Vec<Vec<Option<Token>>>,
+                     empty: &mut Vec<usize>, left: &mut Vec<&Word>| {
+            let count = left.len();
+            if count > empty.len() {
+                // The first words fill the empty units, and the others go
+                // in new units right after them.
+                let mut at = empty.last().map_or(aligned.len(), |u| u + 1);
+                for (k, word) in left.drain(..).enumerate() {
+                    match empty.get(k) {
+                        Some(u) => aligned[*u][w] = Some(word.into()),
+                        None => {
+                            let mut unit = vec![None; n];
+                            unit[w] = Some(word.into());
+                            aligned.insert(at, unit);
+                            at += 1;
+                        }
+                    }
+                }
+            } else {
+                // The first of the empty units still free.
+                let mut first = 0;
+                for (k, word) in left.drain(..).enumerate() {
+                    // A word goes to the most alike of the free units that
+                    // leave enough units for the next words.
+                    let last = empty.len() + k + 1 - count;
+                    let i = (first..last).max_by_key(|i| {
+                        (likeness(word, &aligned[empty[*i]]), Reverse(*i))
+                    }).unwrap();
+                    aligned[empty[i]][w] = Some(word.into());
+                    first = i + 1;
+                }
+            }
+            empty.clear();
+        };
+
+        for edit in edits {
+            match edit {
+                Edit::Equal => {
+                    flush(&mut aligned, &mut empty, &mut left);
+                    let mut unit = old.next().unwrap();
+                    unit[w] = Some((&words[next]).into());
+                    next += 1;
+                    aligned.push(unit);
+                }
+                Edit::Delete => {
+                    empty.push(aligned.len());
+                    aligned.push(old.next().unwrap());
+                }
+                Edit::Insert => {
+                    left.push(&words[next]);
+                    next += 1;
+                }
+            }
+        }
+        flush(&mut aligned, &mut empty, &mut left);
+
+        units = aligned;
+    }
+
+    let transpositions = transpositions(&units, n);
+
+    Collation {
+        sigla: witnesses.iter().map(|(s, _)| s.to_string()).collect(),
+        units,
+        transpositions,
+    }
+}
+
+/// How alike `word` is to the most alike of the tokens of `unit` : the
+/// number of letters of their core texts that can be matched.
+fn likeness(word: &Word, unit: &[Option<Token>]) -> usize {
+    let letters : Vec<char> = word.core.chars().collect();
+
+    unit.iter()
+        .flatten()
+        .map(|t| {
+            let other : Vec<char> = t.core.chars().collect();
+            myers(&letters, &other).iter().filter(|e| **e == Edit::Equal).count()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// The runs of units where a witness alone has words, matching a run of
+/// units where it has none but another witness has the same words.
+fn transpositions(units: &[Vec<Option<Token>>], n: usize) -> Vec<Transposition> {
+    let runs = |keep: &dyn Fn(&[Option<Token>]) -> bool| -> Vec<Range<usize>> {
+        let mut runs : Vec<Range<usize>> = Vec::new();
+        for (i, unit) in units.iter().enumerate() {
+            if !keep(unit) {
+                continue;
+            }
+            match runs.last_mut() {
+                Some(run) if run.end == i => run.end = i + 1,
+                _ => runs.push(i..i + 1),
+            }
+        }
+        runs
+    };
+    let cores = |w: usize, run: &Range<usize>| -> Option<Vec<&str>> {
+        units[run.clone()].iter()
+                          .map(|u| u[w].as_ref().map(|t| t.core.as_str()))
+                          .collect()
+    };
+
+    let mut found : Vec<Transposition> = Vec::new();
+    for w in 0..n {
+        let alone = runs(&|u| u[w].is_some()
+                              && u.iter().flatten().count() == 1);
+        let missing = runs(&|u| u[w].is_none());
+
+        for run in &alone {
+            let words = cores(w, run);
+            let len = run.len();
+            let other = (0..n).filter(|v| *v != w).find_map(|v| {
+                missing.iter()
+                       .flat_map(|m| (m.start..=m.end.saturating_sub(len))
+                                         .map(|s| s..s + len)
+                                         .filter(|r| r.end <= m.end))
+                       .find(|r| cores(v, r) == words)
+                       .map(|r| (v, r))
+            });
+            let Some((v, m)) = other else {
+                continue;
+            };
+            let mirror = found.iter().any(|t| t.units == m && t.other_units == *run);
+            if !mirror {
+                found.push(Transposition {
+                    witness: w,
+                    units: run.clone(),
+                    other: v,
+                    other_units: m,
+                });
+            }
+        }
+    }
+
+    found
+}
+
+impl Collation {
+    /// Whether the witnesses do not all read the same at the unit `u`.
+    pub fn is_variant(&self, u: usize) -> bool {
+        let unit = &self.units[u];
+        unit.iter().any(|t| t.as_ref().map(|t| &t.core)
+                        != unit[0].as_ref().map(|t| &t.core))
+    }
+
+    /// The alignment table, with a line per witness and a column per
+    /// unit. The words are given as found in the witnesses, `-` standing
+    /// for no word.
+    pub fn to_table(&self) -> String {
+        let width = |s: &str| s.chars().count();
+        let siglum_width = self.sigla.iter().map(|s| width(s)).max().unwrap_or(0);
+        let widths : Vec<usize> = self.units
+                                      .iter()
+                                      .map(|u| u.iter()
+                                                .map(|t| width(cell(t)))
+                                                .max()
+                                                .unwrap_or(0))
+                                      .collect();
+
+        let mut out = String::new();
+        for (w, siglum) in self.sigla.iter().enumerate() {
+            let mut line = format!("{siglum}{}", " ".repeat(siglum_width - width(siglum)));
+            for (unit, len) in self.units.iter().zip(&widths) {
+                let text = cell(&unit[w]);
+                write!(line, " {text}{}", " ".repeat(len - width(text))).unwrap();
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// The collation as JSON, laid out as the output of CollateX : the
+    /// sigla, and a table with a row per witness, where each unit is a
+    /// list of tokens with their text (`t`) and core text (`n`). The
+    /// transpositions follow.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"witnesses\":[");
+        let sigla : Vec<_> = self.sigla.iter().map(|s| json_string(s)).collect();
+        out.push_str(&sigla.join(","));
+        out.push_str("],\"table\":[");
+
+        for w in 0..self.sigla.len() {
+            if w > 0 {
+                out.push(',');
+            }
+            out.push('[');
+            for (u, unit) in self.units.iter().enumerate() {
+                if u > 0 {
+                    out.push(',');
+                }
+                match &unit[w] {
+                    Some(t) => {
+                        write!(out, "[{{\"t\":{},\"n\":{}", json_string(&t.text),
+                               json_string(&t.core)).unwrap();
+                        if let Some(r) = t.reference {
+                            write!(out, ",\"ref\":{}", json_string(&r.to_string()))
+                                .unwrap();
+                        }
+                        out.push_str("}]");
+                    }
+                    None => out.push_str("[]"),
+                }
+            }
+            out.push(']');
+        }
+
+        out.push_str("],\"transpositions\":[");
+        let transpositions : Vec<_> =
+            self.transpositions
+                .iter()
+                .map(|t| format!("{{\"witness\":{},\"units\":[{},{}],\
+                                  \"other\":{},\"other_units\":[{},{}]}}",
+                                 json_string(&self.sigla[t.witness]),
+                                 t.units.start, t.units.end,
+                                 json_string(&self.sigla[t.other]),
+                                 t.other_units.start, t.other_units.end))
+                .collect();
+        out.push_str(&transpositions.join(","));
+        out.push_str("]}");
+
+        out
+    }
+
+    /// The variant graph of the collation in the DOT language of
+    /// Graphviz : a node per reading of each unit, and an edge labelled
+    /// with their sigla between the readings the witnesses follow. The
+    /// transpositions are dashed edges.
+    pub fn to_dot(&self) -> String {
+        // The node of each reading, by unit and core text.
+        let mut nodes : BTreeMap<(usize, &str), usize> = BTreeMap::new();
+        let mut out = String::from("digraph collation {\n    rankdir=LR;\n    \
+                                    start [label=\"#\"];\n    end [label=\"#\"];\n");
+
+        for (u, unit) in self.units.iter().enumerate() {
+            for t in unit.iter().flatten() {
+                let next = nodes.len();
+                if let Entry::Vacant(e) = nodes.entry((u, t.core.as_str())) {
+                    e.insert(next);
+                    writeln!(out, "    n{next} [label={}];", json_string(&t.core))
+                        .unwrap();
+                }
+            }
+        }
+
+        // The sigla of each edge, in the order the edges are found, and
+        // the index of each edge in it.
+        let mut edges : Vec<((String, String), Vec<&str>)> = Vec::new();
+        let mut index : BTreeMap<(String, String), usize> = BTreeMap::new();
+        for (w, siglum) in self.sigla.iter().enumerate() {
+            let path = self.units
+                           .iter()
+                           .enumerate()
+                           .filter_map(|(u, unit)| unit[w].as_ref().map(|t| (u, t)))
+                           .map(|(u, t)| format!("n{}", nodes[&(u, t.core.as_str())]));
+            let path : Vec<String> = std::iter::once("start".to_string())
+                                         .chain(path)
+                                         .chain(std::iter::once("end".to_string()))
+                                         .collect();
+            for pair in path.windows(2) {
+                let edge = (pair[0].clone(), pair[1].clone());
+                match index.entry(edge) {
+                    Entry::Occupied(e) => edges[*e.get()].1.push(siglum),
+                    Entry::Vacant(e) => {
+                        edges.push((e.key().clone(), vec![siglum]));
+                        e.insert(edges.len() - 1);
+                    }
+                }
+            }
+        }
+        for ((from, to), sigla) in &edges {
+            writeln!(out, "    {from} -> {to} [label={}];",
+                     json_string(&sigla.join(", "))).unwrap();
+        }
+
+        for t in &self.transpositions {
+            for (u, v) in t.units.clone().zip(t.other_units.clone()) {
+                let a = &self.units[u][t.witness];
+                let b = &self.units[v][t.other];
+                if let (Some(a), Some(b)) = (a, b) {
+                    writeln!(out, "    n{} -> n{} [style=dashed, arrowhead=none];",
+                             nodes[&(u, a.core.as_str())],
+                             nodes[&(v, b.core.as_str())]).unwrap();
+                }
+            }
+        }
+        out.push_str("}\n");
+
+        out
+    }
+}
+
+/// The text of a cell of the alignment table.
+fn cell(t: &Option<Token>) -> &str {
+    t.as_ref().map_or("-", |t| t.text.as_str())
+}
+
+/// `s` as a JSON string, which is also a valid DOT string.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cores(collation: &Collation, w: usize) -> Vec<&str> {
+        collation.units
+                 .iter()
+                 .map(|u| u[w].as_ref().map_or("-", |t| t.core.as_str()))
+                 .collect()
+    }
+
+    #[test]
+    fn test_collate() {
+        let collation = collate(&[
+            ("A", "ὁ δὲ Ἰησοῦς εἶπεν αὐτοῖς"),
+            ("B", "ὁ δὲ εἶπεν αὐτοῖς ὁ Ἰησοῦς"),
+            ("C", "εἶπεν δὲ αὐτοῖς ὁ Ἰησοῦς"),
+        ]).unwrap();
+
+        assert_eq!(cores(&collation, 0), ["ο", "δε", "ιηϲουϲ", "ειπεν", "-", "αυτοιϲ", "-", "-"]);
+        assert_eq!(cores(&collation, 1), ["ο", "δε", "-", "ειπεν", "-", "αυτοιϲ", "ο", "ιηϲουϲ"]);
+        assert_eq!(cores(&collation, 2), ["-", "-", "-", "ειπεν", "δε", "αυτοιϲ", "ο", "ιηϲουϲ"]);
+        assert!(!collation.is_variant(3));
+        assert!(collation.is_variant(4));
+
+        assert_eq!(collation.transpositions, [
+            Transposition { witness: 0, units: 2..3, other: 1, other_units: 7..8 },
+            Transposition { witness: 2, units: 4..5, other: 0, other_units: 1..2 },
+        ]);
+    }
+
+    #[test]
+    fn test_more_words_than_gaps() {
+        let collation = collate(&[
+            ("A", "εἶπεν δὲ αὐτοῖς"),
+            ("B", "εἶπεν καὶ ἔλεγεν αὐτοῖς"),
+        ]).unwrap();
+
+        assert_eq!(cores(&collation, 0), ["ειπεν", "δε", "-", "αυτοιϲ"]);
+        assert_eq!(cores(&collation, 1), ["ειπεν", "και", "ελεγεν", "αυτοιϲ"]);
+        assert_eq!(collation.to_table(), "A εἶπεν δὲ  -      αὐτοῖς\n\
+                                          B εἶπεν καὶ ἔλεγεν αὐτοῖς\n");
+    }
+
+    #[test]
+    fn test_variants() {
+        let collation = collate(&[
+            ("A", "λέγων· τί ποιήσω"),
+            ("B", "λέγων, τί ποιήσωμεν"),
+        ]).unwrap();
+
+        assert_eq!(collation.units.len(), 3);
+        assert!(collation.is_variant(2));
+        assert!(collation.transpositions.is_empty());
+    }
+
+    #[test]
+    fn test_json() {
+        let collation = collate(&[("A", "τί ποιήσω"), ("B\"", "ποιήσω")]).unwrap();
+
+        assert_eq!(collation.to_json(),
+                   "{\"witnesses\":[\"A\",\"B\\\"\"],\"table\":[\
+                    [[{\"t\":\"τί\",\"n\":\"τι\"}],[{\"t\":\"ποιήσω\",\"n\":\"ποιηϲω\"}]],\
+                    [[],[{\"t\":\"ποιήσω\",\"n\":\"ποιηϲω\"}]]],\
+                    \"transpositions\":[]}");
+    }
+
+    #[test]
+    fn test_dot() {
+        let collation = collate(&[("A", "τί ποιήσω"), ("B", "τί ποιήσωμεν")]).unwrap();
+
+        assert_eq!(collation.to_dot(), "digraph collation {
+    rankdir=LR;
+    start [label=\"#\"];
+    end [label=\"#\"];
+    n0 [label=\"τι\"];
+    n1 [label=\"ποιηϲω\"];
+    n2 [label=\"ποιηϲωμεν\"];
+    start -> n0 [label=\"A, B\"];
+    n0 -> n1 [label=\"A\"];
+    n1 -> end [label=\"A\"];
+    n0 -> n2 [label=\"B\"];
+    n2 -> end [label=\"B\"];
+}
+");
+    }
+}
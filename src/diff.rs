@@ -0,0 +1,715 @@
+//! The differences between the core texts of two editions, word by word.
+//!
+//! The words are compared by their core text, so that accents, breathings,
+//! case and punctuation make no difference; the hunks found are given back
+//! with the accented words of each edition and their position in its
+//! source.
+//! # Example :
+//! ```
+//! use gnt_tools::diff::{diff, HunkKind};
+//!
+//! let a = "Εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς λέγων·";
+//! let b = "εἶπεν δὲ καὶ παραβολὴν πρὸς αὐτούς.";
+//!
+//! let hunks = diff(a, b).unwrap();
+//!
+//! assert_eq!(hunks.len(), 2);
+//! assert_eq!(hunks[0].kind, HunkKind::Insert);
+//! assert_eq!(hunks[0].b.text, "καὶ");
+//! assert_eq!(hunks[1].kind, HunkKind::Delete);
+//! assert_eq!(&a[hunks[1].a.span.clone()], "λέγων");
+//! ```
+
+use std::ops::Range;
+
+use crate::reference::{Book, Reference};
+use crate::verse::BookText;
+use crate::{CoreProfile, UnhandledGreekChar};
+
+/// A word of a compared text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word<'a> {
+    /// The core text of the word.
+    pub core: String,
+    /// The word as found in the source, e.g. `λέγων`.
+    pub text: &'a str,
+    /// The byte range of `text` in the source.
+    pub span: Range<usize>,
+    /// The verse of the word, if known.
+    pub reference: Option<Reference>,
+}
+
+/// The words of `s`, without verse references.
+pub fn words(s: &str) -> Result<Vec<Word<'_>>, UnhandledGreekChar> {
+    words_at(s, |span| span, None, &CoreProfile::DEFAULT)
+}
+
+/// Same as [`words`], with the core text of the words following
+/// `profile`.
+pub fn words_with<'a>(s: &'a str, profile: &CoreProfile)
+    -> Result<Vec<Word<'a>>, UnhandledGreekChar> {
+
+    words_at(s, |span| span, None, profile)
+}
+
+/// The words of the verses of `text`, an edition of `book`, with their
+/// references. The spans are offsets in the source of `text`, given by
+/// [`Verse::source_range`](crate::verse::Verse::source_range).
+pub fn book_words<'a>(book: Book, text: &'a BookText)
+    -> Result<Vec<Word<'a>>, UnhandledGreekChar> {
+
+    let mut words = Vec::new();
+    for (chapter, verse) in text.verses() {
+        let reference = Reference::new(book, chapter, verse.number);
+        words.extend(words_at(&verse.text, |span| verse.source_range(span),
+                              Some(reference), &CoreProfile::DEFAULT)?);
+    }
+
+    Ok(words)
+}
+
+/// The words of `s`, whose byte ranges in `s` are turned into spans in
+/// the source by `source`.
+fn words_at<'a>(s: &'a str, source: impl Fn(Range<usize>) -> Range<usize>,
+                reference: Option<Reference>, profile: &CoreProfile)
+    -> Result<Vec<Word<'a>>, UnhandledGreekChar> {
+
+    let core = profile.core_text(s)?;
+    let words = core.words()
+                    .map(|(letters, w)| {
+                        let span = core.source_span(letters).unwrap().bytes();
+                        Word {
+                            core: w.to_string(),
+                            text: &s[span.clone()],
+                            span: source(span),
+                            reference,
+                        }
+                    })
+                    .collect();
+
+    Ok(words)
+}
+
+/// What a [`Hunk`] does to turn the first text into the second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HunkKind {
+    /// Words of the second text only.
+    Insert,
+    /// Words of the first text only.
+    Delete,
+    /// Words of the first text replaced by other words in the second.
+    Replace,
+}
+
+/// The words of one of the texts of a [`Hunk`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Side {
+    /// The indices of the words.
+    pub words: Range<usize>,
+    /// The byte range of the words in the source. Empty for the text
+    /// without words, at the place the words of the other would go.
+    pub span: Range<usize>,
+    /// The words as found in the source, separated by a space.
+    pub text: String,
+    /// The verse of the first word, or of the place of the words for the
+    /// text without words.
+    pub reference: Option<Reference>,
+}
+
+/// A difference between two texts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// What the hunk does.
+    pub kind: HunkKind,
+    /// The words of the first text.
+    pub a: Side,
+    /// The words of the second text.
+    pub b: Side,
+}
+
+/// The differences between the core texts of `a` and `b`.
+pub fn diff(a: &str, b: &str) -> Result<Vec<Hunk>, UnhandledGreekChar> {
+    Ok(diff_words(&words(a)?, &words(b)?))
+}
+
+/// The differences between the core texts of the verses of `a` and `b`,
+/// two editions of `book`.
+pub fn diff_books(book: Book, a: &BookText, b: &BookText)
+    -> Result<Vec<Hunk>, UnhandledGreekChar> {
+
+    Ok(diff_words(&book_words(book, a)?, &book_words(book, b)?))
+}
+
+/// The differences between the words `a` and `b`, compared by their core
+/// text with the Myers algorithm.
+pub fn diff_words(a: &[Word], b: &[Word]) -> Vec<Hunk> {
+    let a_core : Vec<&str> = a.iter().map(|w| w.core.as_str()).collect();
+    let b_core : Vec<&str> = b.iter().map(|w| w.core.as_str()).collect();
+
+    hunks(&myers(&a_core, &b_core))
+        .into_iter()
+        .map(|(ra, rb)| hunk(a, b, ra, rb))
+        .collect()
+}
+
+/// The differences between the core texts of `a` and `b`, ignoring the
+/// word division. See [`diff_continuous_words`].
+/// # Example :
+/// ```
+/// use gnt_tools::diff::{diff, diff_continuous};
+///
+/// let a = "διὰ τί οὐκ ἐπιστεύσατε λέγοντες";
+/// let b = "διατί οὐκ ἐπιστεύσατε λέγων";
+///
+/// // The word division makes a difference word by word…
+/// assert_eq!(diff(a, b).unwrap().len(), 2);
+///
+/// // …but not letter by letter.
+/// let hunks = diff_continuous(a, b).unwrap();
+/// assert_eq!(hunks.len(), 1);
+/// assert_eq!((hunks[0].a.text.as_str(), hunks[0].b.text.as_str()),
+///            ("λέγοντες", "λέγων"));
+/// ```
+pub fn diff_continuous(a: &str, b: &str)
+    -> Result<Vec<Hunk>, UnhandledGreekChar> {
+
+    Ok(diff_continuous_words(&words(a)?, &words(b)?))
+}
+
+/// The differences between the words `a` and `b`, compared as a
+/// continuous text (scriptio continua) : the letters of their core texts
+/// are aligned with the Myers algorithm, whatever the word division.
+///
+/// The differences are then widened to the words of each text holding
+/// them, so that each hunk is a whole variant unit, e.g. `λεγοντεϲ` for
+/// `λεγων` rather than `ο`, `τεϲ` for `ω`. The words differing only by
+/// their division, e.g. `δια τι` and `διατι`, are not a difference.
+pub fn diff_continuous_words(a: &[Word], b: &[Word]) -> Vec<Hunk> {
+    let (a_letters, a_starts) = letters(a);
+    let (b_letters, b_starts) = letters(b);
+    let a_bound = boundaries(&a_starts, a_letters.len());
+    let b_bound = boundaries(&b_starts, b_letters.len());
+    let edits = myers(&a_letters, &b_letters);
+
+    // The places where both texts are between two words and the letters
+    // around agree, with the number of edits before them.
+    let mut cuts = vec![(0, 0, 0)];
+    let (mut i, mut j) = (0, 0);
+    for (n, edit) in edits.iter().enumerate() {
+        match edit {
+            Edit::Equal => {
+                i += 1;
+                j += 1;
+            }
+            Edit::Delete => i += 1,
+            Edit::Insert => j += 1,
+        }
+        let next = edits.get(n + 1);
+        if (*edit == Edit::Equal || next.is_none_or(|e| *e == Edit::Equal))
+           && a_bound[i] && b_bound[j] {
+            cuts.push((i, j, n + 1));
+        }
+    }
+
+    let word = |starts: &[usize], i| starts.partition_point(|s| *s < i);
+    cuts.windows(2)
+        .filter(|c| edits[c[0].2..c[1].2].iter().any(|e| *e != Edit::Equal))
+        .filter_map(|c| {
+            let mut ra = word(&a_starts, c[0].0)..word(&a_starts, c[1].0);
+            let mut rb = word(&b_starts, c[0].1)..word(&b_starts, c[1].1);
+            // Among equally short alignments, the letters may have been
+            // matched across a word both texts have : it is left out.
+            while !ra.is_empty() && !rb.is_empty()
+                  && a[ra.start].core == b[rb.start].core {
+                ra.start += 1;
+                rb.start += 1;
+            }
+            while !ra.is_empty() && !rb.is_empty()
+                  && a[ra.end - 1].core == b[rb.end - 1].core {
+                ra.end -= 1;
+                rb.end -= 1;
+            }
+            let core = |w: &[Word]| -> String {
+                w.iter().map(|w| w.core.as_str()).collect()
+            };
+            (core(&a[ra.clone()]) != core(&b[rb.clone()]))
+                .then(|| hunk(a, b, ra, rb))
+        })
+        .collect()
+}
+
+/// The letters of the core texts of `words`, and the index of the first
+/// letter of each word.
+fn letters(words: &[Word]) -> (Vec<char>, Vec<usize>) {
+    let mut letters = Vec::new();
+    let mut starts = Vec::with_capacity(words.len());
+    for word in words {
+        starts.push(letters.len());
+        letters.extend(word.core.chars());
+    }
+
+    (letters, starts)
+}
+
+/// Whether each place between the `len` letters of a text, from before
+/// the first to after the last, is between two words.
+fn boundaries(starts: &[usize], len: usize) -> Vec<bool> {
+    let mut bound = vec![false; len + 1];
+    for s in starts {
+        bound[*s] = true;
+    }
+    bound[0] = true;
+    bound[len] = true;
+
+    bound
+}
+
+fn hunk(a: &[Word], b: &[Word], ra: Range<usize>, rb: Range<usize>) -> Hunk {
+    let kind = match (ra.is_empty(), rb.is_empty()) {
+        (true, _) => HunkKind::Insert,
+        (_, true) => HunkKind::Delete,
+        _ => HunkKind::Replace,
+    };
+
+    Hunk { kind, a: side(a, ra), b: side(b, rb) }
+}
+
+fn side(words: &[Word], range: Range<usize>) -> Side {
+    let found = &words[range.clone()];
+    let (span, reference) = match (found.first(), found.last()) {
+        (Some(first), Some(last)) => (first.span.start..last.span.end,
+                                      first.reference),
+        _ => match (range.start.checked_sub(1).map(|i| &words[i]),
+                    words.get(range.start)) {
+            (Some(before), _) => (before.span.end..before.span.end,
+                                  before.reference),
+            (None, Some(after)) => (after.span.start..after.span.start,
+                                    after.reference),
+            (None, None) => (0..0, None),
+        },
+    };
+
+    Side {
+        text: found.iter().map(|w| w.text).collect::<Vec<_>>().join(" "),
+        words: range,
+        span,
+        reference,
+    }
+}
+
+/// An element of the edit script turning a sequence into another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Edit {
+    /// The element is in both sequences.
+    Equal,
+    /// The element of the first sequence is not in the second.
+    Delete,
+    /// The element of the second sequence is not in the first.
+    Insert,
+}
+
+/// The shortest edit script turning `a` into `b` (E. Myers, *An O(ND)
+/// Difference Algorithm and Its Variations*, 1986).
+pub(crate) fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Edit> {
+    myers_by(a.len(), b.len(), |i, j| a[i] == b[j])
+}
+
+/// Same as [`myers`] for sequences of lengths `n` and `m`, whose
+/// elements `i` and `j` are the same when `eq(i, j)` is true.
+///
+/// Long sequences are cut in two at the middle snake of their shortest
+/// edit script, searched from both ends, until they are short enough for
+/// the greedy search to keep its trace : the memory used stays linear in
+/// the length of the sequences rather than growing with the number of
+/// edits.
+pub(crate) fn myers_by(n: usize, m: usize, eq: impl Fn(usize, usize) -> bool)
+    -> Vec<Edit> {
+
+    let mut edits = Vec::with_capacity(n + m);
+    let mut forward = Diagonals::new(n, m);
+    let mut backward = Diagonals::new(n, m);
+    conquer(&eq, 0..n, 0..m, &mut forward, &mut backward, &mut edits);
+
+    edits
+}
+
+/// The furthest x reached on each diagonal k, for k from `-max` to `max`.
+struct Diagonals {
+    v: Vec<usize>,
+    offset: isize,
+}
+
+impl Diagonals {
+    fn new(n: usize, m: usize) -> Diagonals {
+        let max = (n + m).div_ceil(2) + 1;
+        Diagonals { v: vec![0; 2 * max + 1], offset: max as isize }
+    }
+}
+
+impl std::ops::Index<isize> for Diagonals {
+    type Output = usize;
+
+    fn index(&self, k: isize) -> &usize {
+        &self.v[(k + self.offset) as usize]
+    }
+}
+
+impl std::ops::IndexMut<isize> for Diagonals {
+    fn index_mut(&mut self, k: isize) -> &mut usize {
+        &mut self.v[(k + self.offset) as usize]
+    }
+}
+
+/// The longest sequences compared by [`greedy`], whose trace takes up to
+/// about `GREEDY_MAX²` positions.
+const GREEDY_MAX : usize = 1024;
+
+/// Pushes to `edits` the shortest edit script turning the elements `a` of
+/// the first sequence into the elements `b` of the second one, found by
+/// the greedy search of Myers, which keeps the furthest positions reached
+/// after each number of edits.
+fn greedy(eq: &impl Fn(usize, usize) -> bool,
+          a: Range<usize>, b: Range<usize>, edits: &mut Vec<Edit>) {
+
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let offset = n + m + 1;
+    let index = |k: isize| (k + offset) as usize;
+    // The furthest x reached on each diagonal k.
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    // The diagonals -d to d of `v` before each number of edits d.
+    let mut trace = Vec::new();
+
+    'search: for d in 0..=n + m {
+        trace.push(v[index(-d)..=index(d)].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && eq(a.start + x as usize, b.start + y as usize) {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let start = edits.len();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let at = |k: isize| v[(k + d) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let (prev_x, prev_y) = if d > 0 {
+            (at(prev_k), at(prev_k) - prev_k)
+        } else {
+            (0, 0)
+        };
+        while x > prev_x && y > prev_y {
+            edits.push(Edit::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            edits.push(if x == prev_x { Edit::Insert } else { Edit::Delete });
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    edits[start..].reverse();
+}
+
+/// Pushes to `edits` the shortest edit script turning the elements `a`
+/// of the first sequence into the elements `b` of the second one.
+fn conquer(eq: &impl Fn(usize, usize) -> bool,
+           mut a: Range<usize>, mut b: Range<usize>,
+           forward: &mut Diagonals, backward: &mut Diagonals,
+           edits: &mut Vec<Edit>) {
+
+    if a.len() + b.len() <= GREEDY_MAX {
+        greedy(eq, a, b, edits);
+        return;
+    }
+
+    while !a.is_empty() && !b.is_empty() && eq(a.start, b.start) {
+        edits.push(Edit::Equal);
+        a.start += 1;
+        b.start += 1;
+    }
+    let mut suffix = 0;
+    while !a.is_empty() && !b.is_empty() && eq(a.end - 1, b.end - 1) {
+        suffix += 1;
+        a.end -= 1;
+        b.end -= 1;
+    }
+
+    if a.is_empty() {
+        edits.extend(std::iter::repeat_n(Edit::Insert, b.len()));
+    } else if b.is_empty() {
+        edits.extend(std::iter::repeat_n(Edit::Delete, a.len()));
+    } else {
+        let (x, y) = middle_snake(eq, a.clone(), b.clone(), forward, backward);
+        conquer(eq, a.start..x, b.start..y, forward, backward, edits);
+        conquer(eq, x..a.end, y..b.end, forward, backward, edits);
+    }
+    edits.extend(std::iter::repeat_n(Edit::Equal, suffix));
+}
+
+/// The start of the middle snake of the shortest edit script turning `a`
+/// into `b`, which neither start nor end with the same elements.
+fn middle_snake(eq: &impl Fn(usize, usize) -> bool,
+                a: Range<usize>, b: Range<usize>,
+                forward: &mut Diagonals, backward: &mut Diagonals)
+    -> (usize, usize) {
+
+    let (n, m) = (a.len(), b.len());
+    let delta = n as isize - m as isize;
+    let odd = delta & 1 == 1;
+    forward[1] = 0;
+    backward[1] = 0;
+
+    for d in 0..=(n + m).div_ceil(2) as isize {
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && forward[k - 1] < forward[k + 1]) {
+                forward[k + 1]
+            } else {
+                forward[k - 1] + 1
+            };
+            let y = (x as isize - k) as usize;
+            let start = (x, y);
+            let mut y = y;
+            while x < n && y < m && eq(a.start + x, b.start + y) {
+                x += 1;
+                y += 1;
+            }
+            forward[k] = x;
+            if odd && (k - delta).abs() < d && x + backward[delta - k] >= n {
+                return (a.start + start.0, b.start + start.1);
+            }
+        }
+
+        for k in (-d..=d).rev().step_by(2) {
+            let mut x = if k == -d || (k != d && backward[k - 1] < backward[k + 1]) {
+                backward[k + 1]
+            } else {
+                backward[k - 1] + 1
+            };
+            let mut y = (x as isize - k) as usize;
+            while x < n && y < m && eq(a.end - x - 1, b.end - y - 1) {
+                x += 1;
+                y += 1;
+            }
+            backward[k] = x;
+            if !odd && (k - delta).abs() <= d && x + forward[delta - k] >= n {
+                return (a.end - x, b.end - y);
+            }
+        }
+    }
+
+    unreachable!("the middle snake is found within (n + m) / 2 edits")
+}
+
+/// The ranges of the two sequences of each run of edits of `edits` that
+/// are not [`Edit::Equal`].
+pub(crate) fn hunks(edits: &[Edit]) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut hunks : Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut in_hunk = false;
+
+    for edit in edits {
+        if *edit != Edit::Equal && !in_hunk {
+            hunks.push((i..i, j..j));
+        }
+        in_hunk = *edit != Edit::Equal;
+        match edit {
+            Edit::Equal => {
+                i += 1;
+                j += 1;
+            }
+            Edit::Delete => i += 1,
+            Edit::Insert => j += 1,
+        }
+        if let Some((a, b)) = hunks.last_mut().filter(|_| in_hunk) {
+            a.end = i;
+            b.end = j;
+        }
+    }
+
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verse::parse;
+
+    #[test]
+    fn test_myers() {
+        use Edit::*;
+
+        let a : Vec<char> = "abcabba".chars().collect();
+        let b : Vec<char> = "cbabac".chars().collect();
+        let edits = myers(&a, &b);
+
+        assert_eq!(edits.iter().filter(|e| **e != Equal).count(), 5);
+        assert_eq!(edits.iter().filter(|e| **e != Insert).count(), a.len());
+        assert_eq!(edits.iter().filter(|e| **e != Delete).count(), b.len());
+
+        assert_eq!(myers::<char>(&[], &[]), []);
+        assert_eq!(myers(&['a'], &[]), [Delete]);
+        assert_eq!(myers(&[], &['a']), [Insert]);
+        assert_eq!(hunks(&[Equal, Delete, Insert, Equal, Insert]),
+                   [(1..2, 1..2), (3..3, 3..4)]);
+    }
+
+    #[test]
+    fn test_myers_long() {
+        // A pseudo-random sequence, and the same one with every 7th
+        // element changed.
+        let mut seed = 7u32;
+        let a : Vec<u32> = (0..3000).map(|_| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            seed >> 16 & 7
+        }).collect();
+        let b : Vec<u32> = a.iter()
+                            .enumerate()
+                            .map(|(i, x)| if i % 7 == 0 { 8 } else { *x })
+                            .collect();
+
+        // The halves around the middle snakes make a script as short as the
+        // one of the greedy search.
+        let edits = myers(&a, &b);
+        let mut shortest = Vec::new();
+        greedy(&|i, j| a[i] == b[j], 0..a.len(), 0..b.len(), &mut shortest);
+        let count = |edits: &[Edit]| edits.iter().filter(|e| **e != Edit::Equal).count();
+        assert_eq!(count(&edits), count(&shortest));
+
+        let (mut i, mut j) = (0, 0);
+        for edit in &edits {
+            match edit {
+                Edit::Equal => {
+                    assert_eq!(a[i], b[j]);
+                    i += 1;
+                    j += 1;
+                }
+                Edit::Delete => i += 1,
+                Edit::Insert => j += 1,
+            }
+        }
+        assert_eq!((i, j), (a.len(), b.len()));
+    }
+
+    /// A text of about the length of a book, and the same text with a word
+    /// changed every 25 words.
+    fn book_sized() -> (String, String) {
+        let vocabulary = ["καὶ", "εἶπεν", "αὐτοῖς", "ὁ", "Ἰησοῦς", "δὲ",
+                          "λέγων", "ἐν", "τῷ", "οὐρανῷ", "πρὸς", "αὐτούς"];
+        let mut seed = 16u32;
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        for i in 0..20000 {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let word = vocabulary[(seed >> 16) as usize % vocabulary.len()];
+            a.push(word);
+            b.push(if i % 25 == 12 { "παραβολὴν" } else { word });
+        }
+
+        (a.join(" "), b.join(" "))
+    }
+
+    #[test]
+    fn test_diff_book_sized() {
+        let (a, b) = book_sized();
+        let hunks = diff(&a, &b).unwrap();
+
+        // Each changed word is deleted and inserted, wherever the shortest
+        // script puts them.
+        let edits : usize = hunks.iter()
+                                 .map(|h| h.a.words.len() + h.b.words.len())
+                                 .sum();
+        assert_eq!(edits, 1600);
+    }
+
+    #[test]
+    fn test_diff_continuous_book_sized() {
+        let (a, b) = book_sized();
+        let (a, b) = (parse(&a), parse(&b));
+        let hunks = diff_continuous_words(&book_words(Book::Luke, &a[0]).unwrap(),
+                                          &book_words(Book::Luke, &b[0]).unwrap());
+
+        // Every changed word is found, in a unit of a word or two.
+        let changed : usize = hunks.iter()
+                                   .map(|h| h.b.text.matches("παραβολὴν").count())
+                                   .sum();
+        assert_eq!(changed, 800);
+        assert!(hunks.iter().all(|h| h.a.words.len() <= 2 && h.b.words.len() <= 2));
+    }
+
+    #[test]
+    fn test_diff() {
+        let a = "καὶ διελογίζετο ἐν ἑαυτῷ λέγων· τί ποιήσω";
+        let b = "καὶ διελογίζετο ἐν αὑτῷ λέγων, τί ποιήσωμεν";
+        let hunks = diff(a, b).unwrap();
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].kind, HunkKind::Replace);
+        assert_eq!((hunks[0].a.text.as_str(), hunks[0].b.text.as_str()),
+                   ("ἑαυτῷ", "αὑτῷ"));
+        assert_eq!(&b[hunks[1].b.span.clone()], "ποιήσωμεν");
+        assert_eq!(hunks[1].a.words, 6..7);
+
+        assert!(diff(a, a).unwrap().is_empty());
+        assert_eq!(diff("", "λέγων").unwrap()[0].a.span, 0..0);
+    }
+
+    #[test]
+    fn test_diff_books() {
+        let s = "16 Εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς λέγων· 17 καὶ διελογίζετο";
+        let a = parse(s);
+        let b = parse("16 Εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς 17 καὶ διελογίζετο");
+        let hunks = diff_books(Book::Luke, &a[0], &b[0]).unwrap();
+
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].a.reference, "Lk 1:16".parse().ok());
+        assert_eq!(&s[hunks[0].a.span.clone()], "λέγων");
+        // The place of the omission in `b` : after its last word of 16.
+        assert_eq!(hunks[0].b.reference, "Lk 1:16".parse().ok());
+    }
+
+    #[test]
+    fn test_diff_continuous() {
+        let a = "ἔλεγεν γὰρ ὅτι ἐξέστη καὶ οἱ παρ' αὐτοῦ";
+        let b = "ελεγον γαρ οτιεξεϲτη αυτον οι παρ αυτου";
+        let hunks = diff_continuous(a, b).unwrap();
+        let units : Vec<_> = hunks.iter()
+                                  .map(|h| (h.kind, h.a.text.as_str(),
+                                            h.b.text.as_str()))
+                                  .collect();
+
+        assert_eq!(units, [
+            (HunkKind::Replace, "ἔλεγεν", "ελεγον"),
+            (HunkKind::Replace, "καὶ", "αυτον"),
+        ]);
+        assert_eq!(hunks[1].a.words, 4..5);
+        assert_eq!(hunks[1].b.words, 3..4);
+
+        let hunks = diff_continuous("δὲ καὶ παραβολὴν", "δὲ παραβολὴν").unwrap();
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].kind, HunkKind::Delete);
+        assert_eq!(hunks[0].a.text, "καὶ");
+        assert_eq!(hunks[0].b.span, 5..5);
+
+        assert!(diff_continuous("δια τι", "διατι").unwrap().is_empty());
+        assert_eq!(diff_continuous("", "λέγων").unwrap()[0].kind, HunkKind::Insert);
+    }
+}
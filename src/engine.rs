@@ -0,0 +1,183 @@
+use std::sync::OnceLock;
+
+use crate::text::core_text_engine;
+use crate::{unhandled_panic, CoreProfile, CoreText, Policy, UnhandledGreekChar};
+
+/// The entries of the table : ASCII (U+0000–U+007F), then the Combining
+/// Diacritical Marks and Greek and Coptic blocks (U+0300–U+03FF), then the
+/// Greek Extended block (U+1F00–U+1FFF).
+const LEN: usize = 0x80 + 0x100 + 0x100;
+
+/// Entry of a dropped character. No character has NUL as core form.
+const DROP: u32 = 0;
+/// Entry of an unhandled character, never a valid `char`.
+const UNHANDLED: u32 = u32::MAX;
+
+/// The core mapping of a [`CoreProfile`], precomputed in a dense table.
+///
+/// The characters of ASCII and of the Greek blocks, i.e. nearly all the
+/// characters of a Greek New Testament, are mapped with a single lookup,
+/// instead of going through their canonical decomposition. The other
+/// characters are folded as before (see [`CoreProfile::fold_core_char`]).
+///
+/// [`core_char`](crate::core_char) and [`core_text`](crate::core_text)
+/// use the engine of the default profile, and [`CoreProfile::core_text`] the
+/// engine of its profile, each built once on first use.
+/// # Example :
+/// ```
+/// use gnt_tools::CoreProfile;
+///
+/// let engine = CoreProfile::new().medial_sigma(true).engine();
+///
+/// assert_eq!(engine.core_char(&'ς'), Some('σ'));
+/// assert_eq!(engine.core_text("Ἰησοῦς").unwrap(), "ιησουσ");
+/// ```
+#[derive(Clone)]
+pub struct CoreEngine {
+    profile: CoreProfile,
+    table: Box<[u32; LEN]>,
+}
+
+impl CoreEngine {
+    /// Builds the engine of `profile`.
+    pub fn new(profile: CoreProfile) -> CoreEngine {
+        let mut table = Box::new([DROP; LEN]);
+
+        for (i, c) in (0..0x80).chain(0x300..0x400)
+                               .chain(0x1F00..0x2000)
+                               .filter_map(char::from_u32)
+                               .enumerate() {
+            table[i] = match profile.try_core_char(&c) {
+                Ok(Some(core_c)) => core_c as u32,
+                Ok(None) => DROP,
+                Err(_) => UNHANDLED,
+            };
+        }
+
+        CoreEngine { profile, table }
+    }
+
+    /// The engine of [`CoreProfile::DEFAULT`], built once.
+    pub(crate) fn default_ref() -> &'static CoreEngine {
+        CoreEngine::cached(CoreProfile::DEFAULT)
+    }
+
+    /// The engine of `profile`, built once.
+    pub(crate) fn cached(profile: CoreProfile) -> &'static CoreEngine {
+        static ENGINES: [OnceLock<CoreEngine>; CoreProfile::COUNT] =
+            [const { OnceLock::new() }; CoreProfile::COUNT];
+        ENGINES[profile.index()].get_or_init(|| CoreEngine::new(profile))
+    }
+
+    /// The profile followed by the engine.
+    pub fn profile(&self) -> &CoreProfile {
+        &self.profile
+    }
+
+    /// Same as [`CoreProfile::core_char`].
+    #[inline]
+    pub fn core_char(&self, c: &char) -> Option<char> {
+        match self.try_core_char(c) {
+            Ok(core_c) => core_c,
+            Err(e) => unhandled_panic(e.ch),
+        }
+    }
+
+    /// Same as [`CoreProfile::try_core_char`].
+    #[inline]
+    pub fn try_core_char(&self, c: &char)
+        -> Result<Option<char>, UnhandledGreekChar> {
+        let i = *c as usize;
+        let entry = match i {
+            0x0000..=0x007F => self.table[i],
+            0x0300..=0x03FF => self.table[i - 0x0300 + 0x80],
+            0x1F00..=0x1FFF => self.table[i - 0x1F00 + 0x180],
+            _ => return self.profile.fold_core_char(c),
+        };
+
+        match entry {
+            DROP => Ok(None),
+            UNHANDLED => Err(UnhandledGreekChar { ch: *c, offset: 0 }),
+            // The entries are built from chars.
+            _ => Ok(char::from_u32(entry)),
+        }
+    }
+
+    /// Same as [`CoreProfile::core_text`].
+    pub fn core_text(&self, s: &str) -> Result<CoreText, UnhandledGreekChar> {
+        core_text_engine(s, self, Policy::Strict)
+    }
+
+    /// Same as [`CoreProfile::core_text_with`].
+    pub fn core_text_with(&self, s: &str, policy: Policy)
+        -> Result<CoreText, UnhandledGreekChar> {
+        core_text_engine(s, self, policy)
+    }
+}
+
+impl std::fmt::Debug for CoreEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoreEngine")
+         .field("profile", &self.profile)
+         .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles() -> [CoreProfile; 5] {
+        [
+            CoreProfile::new(),
+            CoreProfile::new().keep_case(true),
+            CoreProfile::new().medial_sigma(true).keep_final_sigma(true),
+            CoreProfile::new().keep_diaeresis(true).keep_iota_subscript(true),
+            CoreProfile::new().keep_case(true)
+                              .keep_final_sigma(true)
+                              .suspended_nu(true),
+        ]
+    }
+
+    #[test]
+    fn test_engine_matches_fold() {
+        for profile in profiles() {
+            let engine = profile.engine();
+            for c in (0..0x3000).filter_map(char::from_u32) {
+                assert_eq!(engine.try_core_char(&c), profile.fold_core_char(&c),
+                           "U+{:04X} with {:?}", c as u32, profile);
+            }
+        }
+    }
+
+    #[test]
+    fn test_engine_core_text() {
+        let s = "1 Βίβλος γενέσεως Ἰησοῦ Χριστοῦ υἱοῦ Δαυὶδ υἱοῦ Ἀβραάμ. \
+                 2 Ἀβραὰμ ἐγέννησεν τὸν Ἰσαάκ, Ἰσαὰκ δὲ ἐγέννησεν τὸν Ἰακώβ; \
+                 ἀΐδιος ᾠδῇ παραβολὴˉ «ϟϛʹ»";
+
+        for profile in profiles() {
+            let engine = profile.engine();
+            let folded : String = s.chars()
+                                   .filter_map(|c| profile.fold_core_char(&c)
+                                                          .unwrap())
+                                   .collect();
+            let core : String = s.chars()
+                                 .filter_map(|c| engine.core_char(&c))
+                                 .collect();
+            assert_eq!(core, folded);
+        }
+    }
+
+    #[test]
+    fn test_engine_cached() {
+        for profile in profiles() {
+            let engine = CoreEngine::cached(profile);
+            assert_eq!(engine.profile(), &profile);
+            assert!(std::ptr::eq(engine, CoreEngine::cached(profile)));
+        }
+        let indexes : std::collections::HashSet<_> =
+            profiles().iter().map(CoreProfile::index).collect();
+        assert_eq!(indexes.len(), profiles().len());
+    }
+}
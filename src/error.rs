@@ -0,0 +1,21 @@
+use std::error::Error;
+use std::fmt;
+
+/// A character of the Greek and Coptic block (U+0370–U+03FF) that the
+/// core normalization does not handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnhandledGreekChar {
+    /// The character as found in the source.
+    pub ch: char,
+    /// Byte offset of `ch` in the source string.
+    pub offset: usize,
+}
+
+impl fmt::Display for UnhandledGreekChar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Greek unicode character '{}' (U+{:04X}) at byte {} \
+                   is not handled", self.ch, self.ch as u32, self.offset)
+    }
+}
+
+impl Error for UnhandledGreekChar {}
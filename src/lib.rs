@@ -3,7 +3,7 @@
 //! # Examples
 //!
 //! ```
-//! use gnt_tools::core_char;
+//! use gnt_tools::core_text;
 //!
 //! let s = "16 Εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς λέγων·
 //!          ἀνθρώπου τινὸς πλουσίου εὐφόρησεν ἡ χώρα. 17
@@ -14,14 +14,45 @@
 //!           ινοϲπλουϲιουευφορηϲενηχωρακαιδιελογιζετοενεαυτω\
 //!           λεγωντιποιηϲωοτιουκεχωπουϲυναξωτουϲκαρπουϲμου";
 //!
-//! let core_text : String = s.chars()
-//!                           .filter_map(|c| core_char(&c))
-//!                           .collect();
+//! let core = core_text(s).unwrap();
 //!
-//! assert_eq!(core_text.as_str(), s2);
+//! assert_eq!(core.as_str(), s2);
+//!
+//! // Each core letter knows where it comes from in `s`.
+//! let span = core.source_span(0..5).unwrap();
+//! assert_eq!(&s[span.bytes()], "Εἶπεν");
 //! ```
 
-use unicode_normalization::UnicodeNormalization;
+pub mod apparatus;
+mod bytes;
+pub mod collation;
+pub mod diff;
+mod engine;
+mod error;
+pub mod morphgnt;
+pub mod nomina_sacra;
+pub mod numeral;
+pub mod osis;
+pub mod reference;
+mod profile;
+mod removed;
+mod stream;
+mod table;
+mod text;
+pub mod tei;
+pub mod token;
+pub mod usfm;
+pub mod variation;
+pub mod verse;
+pub mod versification;
+
+pub use bytes::core_text_bytes;
+pub use engine::CoreEngine;
+pub use error::UnhandledGreekChar;
+pub use profile::CoreProfile;
+pub use removed::{removed_chars, RemovedCategory, RemovedChar};
+pub use stream::{CoreChars, CoreCharsExt, CoreReadChars, CoreReadExt};
+pub use text::{core_text, core_text_with, CoreText, Policy, Span};
 
 // TODO : doc de la fonction a re-ecrire.
 /// The function helps giving the core text of a greek new testament critical edition.  
@@ -29,10 +60,23 @@ use unicode_normalization::UnicodeNormalization;
 ///
 /// In concrete terms, core_char remove any character that is not in the greek alphabet, puts all greek letters in lowercase, and change all sigmas to lunar sigma.
 ///
+/// The other characters of the Greek and Coptic block (U+0370–U+03FF) are handled this way :
+/// - the symbol variants (e.g., ϐ, ϑ, ϕ, ϖ, ϰ, ϱ, ϵ, ϴ) give their usual letter, and all the lunate, dotted or reversed sigmas and san give lunar sigma;
+/// - the letters used as numerals give stigma ϛ (for digamma ϝ, pamphylian digamma ͷ and stigma ϛ), koppa ϟ (for ϙ and ϟ) and sampi ϡ (for ͳ and ϡ);
+/// - heta ͱ, yot ϳ and the kai symbol ϗ are kept (in lowercase);
+/// - the keraias, the spacing tonos and ypogegrammeni and ϶ are deleted;
+/// - the coptic letters (U+03E2–U+03EF), sho and the unassigned code points are not handled.
+///
+/// The mapping of every code point of the Greek and Coptic, Greek Extended and Combining Diacritical Marks blocks is listed in `src/table/generated.rs`.
+///
 /// So this function :
-/// - does not replace nomina sacras (e.g., κϲ) by their non-abreviated form (resp. κυριοϲ), nor words (e.g., κύριος) by their nomina sacras form (when a nomina sacra form exists) (resp. κϲ).
-/// - is made to delete any character used to encode nomina sacras (e.g., '|', or '(' and ')'), hence |κς| will give κϲ.
-/// - does delete all 'ˉ' characters (so παραβολὴˉ becomes παραβολη, not παραβολην)
+/// - does not replace nomina sacras (e.g., κϲ) by their non-abreviated form (resp. κυριοϲ), nor words (e.g., κύριος) by their nomina sacras form (when a nomina sacra form exists) (resp. κϲ). See [`nomina_sacra`] for that.
+/// - is made to delete any character used to encode nomina sacras (e.g., '|', or '(' and ')'), hence |κς| will give κϲ. See [`token`] to keep track of them.
+/// - does delete all 'ˉ' characters (so παραβολὴˉ becomes παραβολη, not παραβολην), unless [`CoreProfile::suspended_nu`] is set.
+///
+/// [`removed_chars`] tells which characters were deleted, and why.
+/// [`CoreProfile`] allows to change these rules.
+///
 /// TODO : expliquer pourquoi on ne garde pas le point median.
 /// # Example :
 /// ```
@@ -53,47 +97,117 @@ use unicode_normalization::UnicodeNormalization;
 ///
 /// assert_eq!(core_text.as_str(), s2);
 /// ```
-// - gerer les invisibles nu? [ca demandera peut-etre un changement du type de retour]
 // - todo : faire une demande a l'unicode foundation pour ajouter les caracteres grecs onciales.
 // - expliquer pourquoi on ne garde pas le point milieu
 #[inline]
 pub fn core_char(c: &char) -> Option<char> {
-   
-    c.nfd().fold(None, |core_c, i| 
-                
-            // TODO : on fait quoi avec les symboles numeriques?
-
-              /* It could be interesting to check if 
-               * `('α'..='ω').contains(&i)` is faster
-               * than 'α' <= i && i <= 'ω'. */
-              
-              if 'α' <= i && i <= 'ω' 
-              || 'Α' <= i && i <= 'Ω' 
-              {
-                  match i {
-                      'σ' | 'ς' | 'Σ' => Some('ϲ'),
-                      _ => i.to_lowercase().nth(0)
-                  }
-              }
-              else if i == ';'
-                   || 'Ͱ' > i || i > 'Ͽ'
-                   || i == '·'
-              {
-                  core_c
-              }
-              else {
-                  panic!("Greek unicode character '{i}' is \
-                  not handled. If you think it would be \
-                  relevant to handle this character, please \
-                  open an issue on our GitHub repository : \
-                  https://github.com/kylak/gnt-tools/issues.");
-              }
-            )
+    match try_core_char(c) {
+        Ok(core_c) => core_c,
+        Err(e) => unhandled_panic(e.ch),
+    }
+}
+
+/// Same as [`core_char`], but returns an [`UnhandledGreekChar`] error
+/// instead of panicking when `c` is a character of the Greek block
+/// that is not handled.
+///
+/// The `offset` of the error is always `0` since there is no source
+/// string here; [`core_text`] fills it with the position of `c`.
+/// # Example :
+/// ```
+/// use gnt_tools::try_core_char;
+///
+/// assert_eq!(try_core_char(&'Ἰ'), Ok(Some('ι')));
+/// assert_eq!(try_core_char(&'·'), Ok(None));
+/// assert_eq!(try_core_char(&'ϣ').unwrap_err().ch, 'ϣ');
+/// ```
+#[inline]
+pub fn try_core_char(c: &char) -> Result<Option<char>, UnhandledGreekChar> {
+    CoreEngine::default_ref().try_core_char(c)
 }
 
-// fonction qui donne les caracteres supprimes par core_char
-// pub fn new_char(s1, s2)
-// ca permet de pouvoir etre sur des caracteres qu'on a supprime
+/// The letter of the Greek and Coptic block `c` stands for, in the same
+/// case, if [`core_char`] keeps it.
+///
+/// `c` is expected to be already decomposed by NFD.
+#[inline]
+pub(crate) fn base_letter(c: char) -> Option<char> {
+    Some(match c {
+        'α'..='ω' | 'Α'..='Ρ' | 'Σ'..='Ω' => c,
+        // Symbol variants of the usual letters.
+        'ϐ' => 'β',
+        'ϵ' => 'ε',
+        'ϑ' => 'θ',
+        'ϴ' => 'Θ',
+        'ϰ' => 'κ',
+        'ϖ' => 'π',
+        'ϱ' | 'ϼ' => 'ρ',
+        'ϒ' => 'Υ',
+        'ϕ' => 'φ',
+        // Lunate, dotted and reversed sigmas, and san.
+        'ϲ' | 'ͻ' | 'ͼ' | 'ͽ' | 'ϻ' => 'σ',
+        'Ϲ' | 'Ͻ' | 'Ͼ' | 'Ͽ' | 'Ϻ' => 'Σ',
+        // The letters only used as numerals : digamma, pamphylian digamma
+        // and stigma give stigma, archaic and numeric koppa give numeric
+        // koppa, archaic sampi gives sampi.
+        'ϛ' | 'ϝ' | 'ͷ' => 'ϛ',
+        'Ϛ' | 'Ϝ' | 'Ͷ' => 'Ϛ',
+        'ϙ' | 'ϟ' => 'ϟ',
+        'Ϙ' | 'Ϟ' => 'Ϟ',
+        'ͳ' | 'ϡ' => 'ϡ',
+        'Ͳ' | 'Ϡ' => 'Ϡ',
+        // Heta, yot and the kai symbol are kept as they are.
+        'ͱ' | 'Ͱ' | 'ϳ' | 'Ϳ' | 'ϗ' | 'Ϗ' => c,
+        _ => return None,
+    })
+}
+
+/// Whether `c` is a character of the Greek and Coptic block that
+/// [`core_char`] drops.
+///
+/// `c` is expected to be already decomposed by NFD, so e.g. the Greek
+/// question mark is already `;` here.
+#[inline]
+pub(crate) fn is_dropped_greek(c: char) -> bool {
+    matches!(c,
+        // Lower keraia and spacing ypogegrammeni and tonos.
+        '\u{0375}' | '\u{037A}' | '\u{0384}'
+        // Reversed lunate epsilon, a mathematical symbol.
+        | '϶')
+}
+
+/// Whether `c` is a code point of the Greek blocks that is not assigned.
+///
+/// Those of the Greek and Coptic block are not handled like the other
+/// unknown characters of the block; those of the Greek Extended block
+/// must not be dropped like the characters of other blocks.
+#[inline]
+pub(crate) fn is_unassigned_greek(c: char) -> bool {
+    matches!(c,
+        '\u{0378}' | '\u{0379}' | '\u{0380}'..='\u{0383}' | '\u{038B}'
+        | '\u{038D}' | '\u{03A2}'
+        | '\u{1F16}' | '\u{1F17}' | '\u{1F1E}' | '\u{1F1F}' | '\u{1F46}'
+        | '\u{1F47}' | '\u{1F4E}' | '\u{1F4F}' | '\u{1F58}' | '\u{1F5A}'
+        | '\u{1F5C}' | '\u{1F5E}' | '\u{1F7E}' | '\u{1F7F}' | '\u{1FB5}'
+        | '\u{1FC5}' | '\u{1FD4}' | '\u{1FD5}' | '\u{1FDC}' | '\u{1FF0}'
+        | '\u{1FF1}' | '\u{1FF5}' | '\u{1FFF}')
+}
+
+/// Whether `c` is a combining mark.
+pub(crate) fn is_combining(c: char) -> bool {
+    ('\u{0300}'..='\u{036F}').contains(&c)
+    || ('\u{1DC0}'..='\u{1DFF}').contains(&c)
+    || ('\u{20D0}'..='\u{20FF}').contains(&c)
+    || ('\u{FE20}'..='\u{FE2F}').contains(&c)
+}
+
+pub(crate) fn unhandled_panic(c: char) -> ! {
+    panic!("Greek unicode character '{c}' is \
+    not handled. If you think it would be \
+    relevant to handle this character, please \
+    open an issue on our GitHub repository : \
+    https://github.com/kylak/gnt-tools/issues.");
+}
 
 #[cfg(test)]
 mod tests {
@@ -102,7 +216,7 @@ mod tests {
     #[test]
     fn test_core_text() {
         let s = "16 Εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς λέγων·
-            ἀνθρώπου τινὸς πλουσίου εὐφόρησεν ἡ Ͷχώρα. 17 
+            ἀνθρώπου τινὸς πλουσίου εὐφόρησεν ἡ χώρα. 17 
             καὶ διελογίζετο ἐν ἑαυτῷ λέγων· τί ποιήσω, ὅτι 
             οὐκ ἔχω ποῦ συνάξω τοὺς καρπούς μου; ";
 
@@ -110,11 +224,131 @@ mod tests {
             ινοϲπλουϲιουευφορηϲενηχωρακαιδιελογιζετοενεαυτω\
             λεγωντιποιηϲωοτιουκεχωπουϲυναξωτουϲκαρπουϲμου";
 
-        let core_text : String = s.chars()
-                                  .filter_map(|c| core_char(&c))
-                                  .collect();
+        let core = core_text(s).unwrap();
+
+        assert_eq!(core.as_str(), s2);
+    }
+
+    #[test]
+    fn test_greek_and_coptic_block() {
+        // The core form of each code point from U+0370 to U+03FF : '-' when
+        // it is deleted, '!' when it is not handled.
+        let expected = "ͱͱϡϡ--ϛϛ!!-ϲϲϲ-ϳ\
+                        !!!!--α-εηι!ο!υω\
+                        ιαβγδεζηθικλμνξο\
+                        πρ!ϲτυφχψωιυαεηι\
+                        υαβγδεζηθικλμνξο\
+                        πρϲϲτυφχψωιυουωϗ\
+                        βθυυυφπϗϟϟϛϛϛϛϟϟ\
+                        ϡϡ!!!!!!!!!!!!!!\
+                        κρϲϳθε-!!ϲϲϲρϲϲϲ";
+
+        for (c, e) in ('\u{0370}'..='\u{03FF}').zip(expected.chars()) {
+            let got = match try_core_char(&c) {
+                Ok(Some(l)) => l,
+                Ok(None) => '-',
+                Err(_) => '!',
+            };
+            assert_eq!(got, e, "U+{:04X} '{c}'", c as u32);
+        }
+        assert_eq!(expected.chars().count(), 0x90);
+
+        // The pamphylian digamma is a digamma, not a mark.
+        assert_eq!(core_text("Ͷαυ ͷ").unwrap(), "ϛαυϛ");
+        assert_eq!(CoreProfile::new().keep_case(true).core_char(&'Ͷ'), Some('Ϛ'));
+    }
+
+    #[test]
+    fn test_greek_extended_block() {
+        // The core form of each code point from U+1F00 to U+1FFF : '-' when
+        // it is deleted, '!' when it is not handled, which is the case of
+        // the code points not assigned.
+        let expected = "αααααααααααααααα\
+                        εεεεεε!!εεεεεε!!\
+                        ηηηηηηηηηηηηηηηη\
+                        ιιιιιιιιιιιιιιιι\
+                        οοοοοο!!οοοοοο!!\
+                        υυυυυυυυ!υ!υ!υ!υ\
+                        ωωωωωωωωωωωωωωωω\
+                        ααεεηηιιοουυωω!!\
+                        αααααααααααααααα\
+                        ηηηηηηηηηηηηηηηη\
+                        ωωωωωωωωωωωωωωωω\
+                        ααααα!ααααααα-ι-\
+                        --ηηη!ηηεεηηη---\
+                        ιιιι!!ιιιιιι!---\
+                        υυυυρρυυυυυυρ---\
+                        !!ωωω!ωωοοωωω--!";
+
+        for (c, e) in ('\u{1F00}'..='\u{1FFF}').zip(expected.chars()) {
+            let got = match try_core_char(&c) {
+                Ok(Some(l)) => l,
+                Ok(None) => '-',
+                Err(_) => '!',
+            };
+            assert_eq!(got, e, "U+{:04X} '{c}'", c as u32);
+        }
+        assert_eq!(expected.chars().count(), 0x100);
+    }
+
+    #[test]
+    fn test_spacing_marks() {
+        // Koronis, psili, dasia, perispomeni, dialytika tonos, varia, oxia
+        // and their combinations, wherever their block is.
+        for c in "᾽᾿῾῀΅`´῁῍῎῏῝῞῟῭΅`´῾΄ͺ¨".chars() {
+            assert_eq!(try_core_char(&c), Ok(None), "U+{:04X}", c as u32);
+        }
+        assert_eq!(core_text("ὁ δ᾽ ἀπεκρίθη῾").unwrap(), "οδαπεκριθη");
+    }
+
+    #[test]
+    #[should_panic(expected = "is not handled")]
+    fn test_core_char_panics_on_unhandled() {
+        core_char(&'ϣ');
+    }
+
+    #[test]
+    fn test_core_text_strict() {
+        let s = "λόγος ϣ καὶ ϣ";
+
+        assert_eq!(core_text("ὁ λόγος·").unwrap(), "ολογοϲ");
+        assert_eq!(core_text(s), Err(UnhandledGreekChar {
+            ch: 'ϣ',
+            offset: s.find('ϣ').unwrap(),
+        }));
+    }
+
+    #[test]
+    fn test_core_text_lenient_reports_all() {
+        let s = "ϣλόγος ϣ";
+        let core = core_text_with(s, Policy::Lenient).unwrap();
+
+        assert_eq!(core, "λογοϲ");
+        assert_eq!(core.unhandled().iter().map(|e| e.offset).collect::<Vec<_>>(),
+                   vec![0, s.rfind('ϣ').unwrap()]);
+    }
+
+    #[test]
+    fn test_core_text_spans() {
+        let s = "ἀνθρώπου τινὸς, |κς|";
+        let core = core_text(s).unwrap();
+
+        assert_eq!(core.len(), core.as_str().chars().count());
+        for (c, span) in core.as_str().chars().zip(core.spans()) {
+            assert_eq!(core_char(&s[span.bytes()].chars().next().unwrap()),
+                       Some(c));
+            assert_eq!(s.chars().nth(span.char_start), s[span.bytes()].chars().next());
+        }
+
+        let span = core.source_span(8..13).unwrap();
+        assert_eq!(&s[span.bytes()], "τινὸς");
+        assert_eq!(span.chars(), 9..14);
+
+        let span = core.source_span(13..15).unwrap();
+        assert_eq!(&s[span.bytes()], "κς");
 
-         assert_eq!(core_text.as_str(), s2);
+        assert_eq!(core.source_span(3..3), None);
+        assert_eq!(core.source_span(14..16), None);
     }
 
     /*
@@ -0,0 +1,478 @@
+//! Reader of the MorphGNT files of the SBLGNT, one word per line :
+//!
+//! ```text
+//! 031216 V- 3AAI-S-- Εἶπεν Εἶπεν εἶπε(ν) λέγω
+//! ```
+//!
+//! The columns are the reference (book, chapter and verse on two digits
+//! each), the part of speech, the parsing code, the text (with
+//! punctuation), the word, the normalized word and the lemma. [`words`]
+//! gives the words to [`diff`] and [`collation`](crate::collation), to
+//! compare MorphGNT files.
+//! # Example :
+//! ```
+//! use gnt_tools::morphgnt::{read, PartOfSpeech, Tense};
+//! use gnt_tools::reference::Book;
+//!
+//! let file = "031216 V- 3AAI-S-- Εἶπεν Εἶπεν εἶπε(ν) λέγω
+//! 031216 C- -------- δὲ δὲ δέ δέ\n";
+//! let words : Vec<_> = read(file.as_bytes()).collect::<Result<_, _>>().unwrap();
+//!
+//! assert_eq!(words[0].reference.book, Book::Luke);
+//! assert_eq!(words[0].pos, PartOfSpeech::Verb);
+//! assert_eq!(words[0].parsing.tense, Some(Tense::Aorist));
+//! assert_eq!(words[0].lemma, "λέγω");
+//! assert_eq!(words[0].core, "ειπεν");
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, ErrorKind};
+
+use crate::core_text;
+use crate::diff;
+use crate::reference::{Book, Reference};
+
+/// A word of a MorphGNT file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    /// The verse of the word.
+    pub reference: Reference,
+    /// The part of speech.
+    pub pos: PartOfSpeech,
+    /// The parsing code.
+    pub parsing: Parsing,
+    /// The word as in the text, with its punctuation, e.g. `λέγων·`.
+    pub text: String,
+    /// The word without punctuation, e.g. `λέγων`.
+    pub word: String,
+    /// The normalized word, e.g. with its accent as out of context.
+    pub normalized: String,
+    /// The lemma.
+    pub lemma: String,
+    /// The core text of `word`.
+    pub core: String,
+}
+
+/// A part of speech of MorphGNT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartOfSpeech {
+    /// `A-`
+    Adjective,
+    /// `C-`
+    Conjunction,
+    /// `D-`
+    Adverb,
+    /// `I-`
+    Interjection,
+    /// `N-`
+    Noun,
+    /// `P-`
+    Preposition,
+    /// `RA`
+    Article,
+    /// `RD`
+    DemonstrativePronoun,
+    /// `RI`
+    InterrogativePronoun,
+    /// `RP`
+    PersonalPronoun,
+    /// `RR`
+    RelativePronoun,
+    /// `V-`
+    Verb,
+    /// `X-`
+    Particle,
+}
+
+impl PartOfSpeech {
+    fn from_code(code: &str) -> Option<PartOfSpeech> {
+        Some(match code {
+            "A-" => PartOfSpeech::Adjective,
+            "C-" => PartOfSpeech::Conjunction,
+            "D-" => PartOfSpeech::Adverb,
+            "I-" => PartOfSpeech::Interjection,
+            "N-" => PartOfSpeech::Noun,
+            "P-" => PartOfSpeech::Preposition,
+            "RA" => PartOfSpeech::Article,
+            "RD" => PartOfSpeech::DemonstrativePronoun,
+            "RI" => PartOfSpeech::InterrogativePronoun,
+            "RP" => PartOfSpeech::PersonalPronoun,
+            "RR" => PartOfSpeech::RelativePronoun,
+            "V-" => PartOfSpeech::Verb,
+            "X-" => PartOfSpeech::Particle,
+            _ => return None,
+        })
+    }
+}
+
+/// The parsing code of a word, e.g. `3AAI-S--`. Each feature is `None`
+/// when it does not apply to the word.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Parsing {
+    /// The person, `1`, `2` or `3`.
+    pub person: Option<u8>,
+    /// The tense of a verb.
+    pub tense: Option<Tense>,
+    /// The voice of a verb.
+    pub voice: Option<Voice>,
+    /// The mood of a verb.
+    pub mood: Option<Mood>,
+    /// The case.
+    pub case: Option<Case>,
+    /// The number.
+    pub number: Option<Number>,
+    /// The gender.
+    pub gender: Option<Gender>,
+    /// The degree of an adjective.
+    pub degree: Option<Degree>,
+}
+
+/// A tense of a verb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tense {
+    Present,
+    Imperfect,
+    Future,
+    Aorist,
+    Perfect,
+    Pluperfect,
+}
+
+/// A voice of a verb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Voice {
+    Active,
+    Middle,
+    Passive,
+}
+
+/// A mood of a verb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mood {
+    Indicative,
+    Imperative,
+    Subjunctive,
+    Optative,
+    Infinitive,
+    Participle,
+}
+
+/// A case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Case {
+    Nominative,
+    Genitive,
+    Dative,
+    Accusative,
+    Vocative,
+}
+
+/// A grammatical number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Number {
+    Singular,
+    Plural,
+}
+
+/// A gender.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gender {
+    Masculine,
+    Feminine,
+    Neuter,
+}
+
+/// A degree of an adjective.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Degree {
+    Comparative,
+    Superlative,
+}
+
+impl Parsing {
+    fn from_code(code: &str) -> Option<Parsing> {
+        let c : Vec<char> = code.chars().collect();
+        if c.len() != 8 {
+            return None;
+        }
+
+        /// The feature of the code `k`, `None` for `-`.
+        fn feature<T>(k: char, f: impl Fn(char) -> Option<T>)
+            -> Option<Option<T>> {
+            match k {
+                '-' => Some(None),
+                _ => f(k).map(Some),
+            }
+        }
+
+        Some(Parsing {
+            person: feature(c[0], |k| k.to_digit(10)
+                                       .filter(|p| (1..=3).contains(p))
+                                       .map(|p| p as u8))?,
+            tense: feature(c[1], |k| Some(match k {
+                'P' => Tense::Present,
+                'I' => Tense::Imperfect,
+                'F' => Tense::Future,
+                'A' => Tense::Aorist,
+                'X' => Tense::Perfect,
+                'Y' => Tense::Pluperfect,
+                _ => return None,
+            }))?,
+            voice: feature(c[2], |k| Some(match k {
+                'A' => Voice::Active,
+                'M' => Voice::Middle,
+                'P' => Voice::Passive,
+                _ => return None,
+            }))?,
+            mood: feature(c[3], |k| Some(match k {
+                'I' => Mood::Indicative,
+                'D' => Mood::Imperative,
+                'S' => Mood::Subjunctive,
+                'O' => Mood::Optative,
+                'N' => Mood::Infinitive,
+                'P' => Mood::Participle,
+                _ => return None,
+            }))?,
+            case: feature(c[4], |k| Some(match k {
+                'N' => Case::Nominative,
+                'G' => Case::Genitive,
+                'D' => Case::Dative,
+                'A' => Case::Accusative,
+                'V' => Case::Vocative,
+                _ => return None,
+            }))?,
+            number: feature(c[5], |k| Some(match k {
+                'S' => Number::Singular,
+                'P' => Number::Plural,
+                _ => return None,
+            }))?,
+            gender: feature(c[6], |k| Some(match k {
+                'M' => Gender::Masculine,
+                'F' => Gender::Feminine,
+                'N' => Gender::Neuter,
+                _ => return None,
+            }))?,
+            degree: feature(c[7], |k| Some(match k {
+                'C' => Degree::Comparative,
+                'S' => Degree::Superlative,
+                _ => return None,
+            }))?,
+        })
+    }
+}
+
+/// A line of a MorphGNT file that cannot be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWordError {
+    /// The number of the line, from 1.
+    pub line: usize,
+    /// The column that cannot be read, e.g. `reference`.
+    pub column: &'static str,
+}
+
+impl fmt::Display for ParseWordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} at line {} of the MorphGNT file",
+               self.column, self.line)
+    }
+}
+
+impl Error for ParseWordError {}
+
+/// Reads the line `line` of a MorphGNT file, whose number is `number`.
+pub fn parse_line(line: &str, number: usize) -> Result<Word, ParseWordError> {
+    let err = |column| ParseWordError { line: number, column };
+    let mut columns = line.split_whitespace();
+    let mut next = |column| columns.next().ok_or_else(|| err(column));
+
+    let bcv = next("reference")?;
+    let pos = next("part of speech")?;
+    let parsing = next("parsing code")?;
+    let text = next("text")?;
+    let word = next("word")?;
+    let normalized = next("normalized word")?;
+    let lemma = next("lemma")?;
+
+    Ok(Word {
+        reference: reference(bcv).ok_or_else(|| err("reference"))?,
+        pos: PartOfSpeech::from_code(pos).ok_or_else(|| err("part of speech"))?,
+        parsing: Parsing::from_code(parsing).ok_or_else(|| err("parsing code"))?,
+        core: core_text(word).map_err(|_| err("word"))?.into_string(),
+        text: text.to_string(),
+        word: word.to_string(),
+        normalized: normalized.to_string(),
+        lemma: lemma.to_string(),
+    })
+}
+
+/// Reads a `BBCCVV` reference, the books being numbered from `01` for
+/// Matthew (or from `40`, as in the numbering of the whole Bible).
+fn reference(bcv: &str) -> Option<Reference> {
+    if bcv.len() != 6 || !bcv.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let n = |i: usize| bcv[i..i + 2].parse::<u32>().ok();
+    let book = match n(0)? {
+        b @ 1..=27 => Book::ALL[b as usize - 1],
+        b @ 40..=66 => Book::ALL[b as usize - 40],
+        _ => return None,
+    };
+
+    Some(Reference::new(book, n(2)?, n(4)?))
+}
+
+/// The words of the MorphGNT file read from `reader`. Empty lines are
+/// skipped; a line that cannot be read gives an error of kind
+/// [`ErrorKind::InvalidData`] with the [`ParseWordError`] as inner error.
+pub fn read(reader: impl BufRead) -> impl Iterator<Item = io::Result<Word>> {
+    reader.lines()
+          .enumerate()
+          .filter(|(_, line)| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+          .map(|(i, line)| {
+              parse_line(&line?, i + 1)
+                  .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+          })
+}
+
+/// Splits `words` into runs of words of the same verse, in their order.
+/// # Example :
+/// ```
+/// use gnt_tools::morphgnt::{read, verses};
+///
+/// let file = "031216 V- 3AAI-S-- Εἶπεν Εἶπεν εἶπε(ν) λέγω
+/// 031217 C- -------- καὶ καὶ καί καί\n";
+/// let words : Vec<_> = read(file.as_bytes()).collect::<Result<_, _>>().unwrap();
+/// let verses : Vec<_> = verses(&words).map(|(r, w)| (r.to_string(), w.len()))
+///                                     .collect();
+///
+/// assert_eq!(verses, [("Luke 12:16".to_string(), 1), ("Luke 12:17".to_string(), 1)]);
+/// ```
+pub fn verses(words: &[Word]) -> impl Iterator<Item = (Reference, &[Word])> {
+    words.chunk_by(|a, b| a.reference == b.reference)
+         .map(|run| (run[0].reference, run))
+}
+
+/// The text of `words`, i.e. their `text` joined by spaces.
+pub fn text(words: &[Word]) -> String {
+    words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+/// `words` as the words of [`diff`], with their core text and their
+/// reference, to compare or collate MorphGNT files. The spans are byte
+/// ranges in the [`text`] of `words`.
+/// # Example :
+/// ```
+/// use gnt_tools::diff::diff_words;
+/// use gnt_tools::morphgnt::{read, text, words};
+///
+/// let a = "031216 V- 3AAI-S-- Εἶπεν Εἶπεν εἶπε(ν) λέγω
+/// 031216 C- -------- δὲ δὲ δέ δέ
+/// 031216 N- ----ASF- παραβολὴν παραβολὴν παραβολήν παραβολή\n";
+/// let b = "031216 V- 3AAI-S-- Εἶπεν Εἶπεν εἶπε(ν) λέγω
+/// 031216 N- ----ASF- παραβολὴν παραβολὴν παραβολήν παραβολή\n";
+/// let a : Vec<_> = read(a.as_bytes()).collect::<Result<_, _>>().unwrap();
+/// let b : Vec<_> = read(b.as_bytes()).collect::<Result<_, _>>().unwrap();
+///
+/// let hunks = diff_words(&words(&a), &words(&b));
+///
+/// assert_eq!(hunks.len(), 1);
+/// assert_eq!(hunks[0].a.reference.unwrap().to_string(), "Luke 12:16");
+/// assert_eq!(&text(&a)[hunks[0].a.span.clone()], "δὲ");
+/// ```
+pub fn words(words: &[Word]) -> Vec<diff::Word<'_>> {
+    let mut start = 0;
+
+    words.iter()
+         .map(|w| {
+             // The word without the punctuation of the text around it.
+             let span = match w.text.find(w.word.as_str()) {
+                 Some(i) => start + i..start + i + w.word.len(),
+                 None => start..start + w.text.len(),
+             };
+             start += w.text.len() + 1;
+             diff::Word {
+                 core: w.core.clone(),
+                 text: &w.word,
+                 span,
+                 reference: Some(w.reference),
+             }
+         })
+         .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        let word = parse_line("010101 N- ----NSF- Βίβλος Βίβλος βίβλος βίβλος", 1)
+                       .unwrap();
+
+        assert_eq!(word.reference, "Mt 1:1".parse().unwrap());
+        assert_eq!(word.pos, PartOfSpeech::Noun);
+        assert_eq!(word.parsing, Parsing {
+            case: Some(Case::Nominative),
+            number: Some(Number::Singular),
+            gender: Some(Gender::Feminine),
+            ..Parsing::default()
+        });
+        assert_eq!(word.core, "βιβλοϲ");
+
+        let word = parse_line("400101 A- ----NSM-C μείζων μείζων μείζων μέγας", 1);
+        assert_eq!(word.unwrap_err().column, "parsing code");
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let file = "010101 N- ----NSF- Βίβλος Βίβλος βίβλος βίβλος\n\n\
+                    990101 N- ----NSF- Βίβλος Βίβλος βίβλος βίβλος\n";
+        let words : Vec<_> = read(file.as_bytes()).collect();
+
+        assert_eq!(words.len(), 2);
+        let e = words[1].as_ref().unwrap_err();
+        let inner = e.get_ref().unwrap().downcast_ref::<ParseWordError>().unwrap();
+        assert_eq!(inner, &ParseWordError { line: 3, column: "reference" });
+    }
+
+    #[test]
+    fn test_compare_files() {
+        use crate::collation::collate_words;
+        use crate::diff::{diff_words, HunkKind};
+
+        let read_all = |file: &str| -> Vec<Word> {
+            read(file.as_bytes()).collect::<Result<_, _>>().unwrap()
+        };
+        let a = read_all("031217 C- -------- καὶ καὶ καί καί
+                          031217 V- 3IMI-S-- διελογίζετο διελογίζετο διελογίζετο διαλογίζομαι
+                          031217 P- -------- ἐν ἐν ἐν ἐν
+                          031217 RP ----DSM- ἑαυτῷ ἑαυτῷ ἑαυτῷ ἑαυτοῦ
+                          031217 V- -PAPNSM- λέγων· λέγων λέγων λέγω\n");
+        let b = read_all("031217 C- -------- καὶ καὶ καί καί
+                          031217 V- 3IMI-S-- διελογίζετο διελογίζετο διελογίζετο διαλογίζομαι
+                          031217 V- -PAPNSM- λέγων λέγων λέγων λέγω
+                          031217 P- -------- ἐν ἐν ἐν ἐν
+                          031217 RP ----DSM- αὐτῷ αὐτῷ αὐτῷ αὐτός\n");
+        let (wa, wb) = (words(&a), words(&b));
+
+        assert_eq!(text(&a), "καὶ διελογίζετο ἐν ἑαυτῷ λέγων·");
+        assert_eq!(wa[4].core, "λεγων");
+        assert_eq!(&text(&a)[wa[4].span.clone()], "λέγων");
+
+        let hunks = diff_words(&wa, &wb);
+        let kinds : Vec<_> = hunks.iter().map(|h| h.kind).collect();
+        assert_eq!(kinds, [HunkKind::Delete, HunkKind::Insert]);
+        assert_eq!(&text(&a)[hunks[0].a.span.clone()], "ἐν ἑαυτῷ");
+        assert_eq!(&text(&b)[hunks[1].b.span.clone()], "ἐν αὐτῷ");
+        assert!(hunks.iter().all(|h| h.a.reference == Some(a[0].reference)));
+
+        let collation = collate_words(&[("SBLGNT", &wa), ("B", &wb)]);
+        assert_eq!(collation.sigla, ["SBLGNT", "B"]);
+        let shared = collation.units
+                              .iter()
+                              .filter(|u| u.iter().all(Option::is_some))
+                              .count();
+        assert_eq!(shared, 3);
+    }
+}
@@ -0,0 +1,297 @@
+//! Nomina sacra : the contracted forms of sacred names used by the
+//! manuscripts (e.g. `κϲ` for `κυριοϲ`).
+//!
+//! Editions with and without contractions give huge spurious differences
+//! when their core texts are compared. [`expand`] and [`contract`] put two
+//! witnesses on the same footing before comparing them.
+//!
+//! The forms are given in the core form of the default
+//! [`CoreProfile`](crate::CoreProfile) : lowercase, lunate sigma, no
+//! diacritics. Since core text is scriptio continua, the passes work on
+//! the words of the [`CoreText`], never inside a word. To work only from
+//! what the manuscript marks as nomina sacra, see [`crate::token`].
+//! # Example :
+//! ```
+//! use gnt_tools::core_text;
+//! use gnt_tools::nomina_sacra::{contract, expand};
+//!
+//! let printed = core_text("ὁ κύριος Ἰησοῦς Χριστός").unwrap();
+//! let uncial = core_text("ο |κς| |ις| |χς|").unwrap();
+//!
+//! assert_eq!(contract(&printed).as_str(), uncial.as_str());
+//! assert_eq!(expand(&uncial).as_str(), printed.as_str());
+//! ```
+
+use crate::CoreText;
+
+/// A contracted form of a nomen sacrum, with its full form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NomenSacrum {
+    /// The lemma of the word, e.g. `κυριοϲ`.
+    pub lemma: &'static str,
+    /// The contracted form, e.g. `κυ`.
+    pub contracted: &'static str,
+    /// The full form, e.g. `κυριου`.
+    pub full: &'static str,
+}
+
+const fn ns(lemma: &'static str, contracted: &'static str, full: &'static str)
+    -> NomenSacrum {
+    NomenSacrum { lemma, contracted, full }
+}
+
+/// The standard nomina sacra, with all their case forms.
+///
+/// When several contracted forms share the same full form, the first one
+/// is the one used by [`contract`]. Contracted forms that are also common
+/// words (e.g. `ανω` for `ανθρωπω`, `υϲ` for `υιοϲ`, also `ὗς` "sow", or
+/// `κω` for `κυριω`, also the island of Cos) are left out, see [`MARKED`].
+pub const TABLE: &[NomenSacrum] = &[
+    ns("θεοϲ", "θϲ", "θεοϲ"),
+    ns("θεοϲ", "θυ", "θεου"),
+    ns("θεοϲ", "θω", "θεω"),
+    ns("θεοϲ", "θν", "θεον"),
+    ns("θεοϲ", "θε", "θεε"),
+    ns("θεοϲ", "θοι", "θεοι"),
+    ns("θεοϲ", "θων", "θεων"),
+    ns("θεοϲ", "θουϲ", "θεουϲ"),
+
+    ns("κυριοϲ", "κϲ", "κυριοϲ"),
+    ns("κυριοϲ", "κυ", "κυριου"),
+    ns("κυριοϲ", "κν", "κυριον"),
+    ns("κυριοϲ", "κε", "κυριε"),
+    ns("κυριοϲ", "κοι", "κυριοι"),
+    ns("κυριοϲ", "κων", "κυριων"),
+    ns("κυριοϲ", "κοιϲ", "κυριοιϲ"),
+    ns("κυριοϲ", "κουϲ", "κυριουϲ"),
+
+    ns("ιηϲουϲ", "ιϲ", "ιηϲουϲ"),
+    ns("ιηϲουϲ", "ιηϲ", "ιηϲουϲ"),
+    ns("ιηϲουϲ", "ιυ", "ιηϲου"),
+    ns("ιηϲουϲ", "ιηυ", "ιηϲου"),
+    ns("ιηϲουϲ", "ιν", "ιηϲουν"),
+    ns("ιηϲουϲ", "ιην", "ιηϲουν"),
+
+    ns("χριϲτοϲ", "χϲ", "χριϲτοϲ"),
+    ns("χριϲτοϲ", "χρϲ", "χριϲτοϲ"),
+    ns("χριϲτοϲ", "χυ", "χριϲτου"),
+    ns("χριϲτοϲ", "χρυ", "χριϲτου"),
+    ns("χριϲτοϲ", "χω", "χριϲτω"),
+    ns("χριϲτοϲ", "χρω", "χριϲτω"),
+    ns("χριϲτοϲ", "χν", "χριϲτον"),
+    ns("χριϲτοϲ", "χρν", "χριϲτον"),
+    ns("χριϲτοϲ", "χε", "χριϲτε"),
+
+    ns("υιοϲ", "υυ", "υιου"),
+    ns("υιοϲ", "υω", "υιω"),
+    ns("υιοϲ", "υν", "υιον"),
+    ns("υιοϲ", "υε", "υιε"),
+
+    ns("πνευμα", "πνα", "πνευμα"),
+    ns("πνευμα", "πνϲ", "πνευματοϲ"),
+    ns("πνευμα", "πνι", "πνευματι"),
+    ns("πνευμα", "πνατα", "πνευματα"),
+    ns("πνευμα", "πνατων", "πνευματων"),
+    ns("πνευμα", "πναϲι", "πνευμαϲι"),
+    ns("πνευματικοϲ", "πνικοϲ", "πνευματικοϲ"),
+    ns("πνευματικοϲ", "πνικον", "πνευματικον"),
+    ns("πνευματικοϲ", "πνικα", "πνευματικα"),
+    ns("πνευματικοϲ", "πνικοι", "πνευματικοι"),
+
+    ns("ανθρωποϲ", "ανοϲ", "ανθρωποϲ"),
+    ns("ανθρωποϲ", "ανου", "ανθρωπου"),
+    ns("ανθρωποϲ", "ανον", "ανθρωπον"),
+    ns("ανθρωποϲ", "ανε", "ανθρωπε"),
+    ns("ανθρωποϲ", "ανοι", "ανθρωποι"),
+    ns("ανθρωποϲ", "ανων", "ανθρωπων"),
+    ns("ανθρωποϲ", "ανοιϲ", "ανθρωποιϲ"),
+    ns("ανθρωποϲ", "ανουϲ", "ανθρωπουϲ"),
+
+    ns("ουρανοϲ", "ουνοϲ", "ουρανοϲ"),
+    ns("ουρανοϲ", "ουνου", "ουρανου"),
+    ns("ουρανοϲ", "ουνω", "ουρανω"),
+    ns("ουρανοϲ", "ουνον", "ουρανον"),
+    ns("ουρανοϲ", "ουνοι", "ουρανοι"),
+    ns("ουρανοϲ", "ουνων", "ουρανων"),
+    ns("ουρανοϲ", "ουνοιϲ", "ουρανοιϲ"),
+    ns("ουρανοϲ", "ουνουϲ", "ουρανουϲ"),
+
+    ns("πατηρ", "πηρ", "πατηρ"),
+    ns("πατηρ", "πρϲ", "πατροϲ"),
+    ns("πατηρ", "πρι", "πατρι"),
+    ns("πατηρ", "πρα", "πατερα"),
+    ns("πατηρ", "πρεϲ", "πατερεϲ"),
+    ns("πατηρ", "πρων", "πατερων"),
+    ns("πατηρ", "πραϲι", "πατραϲι"),
+    ns("πατηρ", "πραϲ", "πατεραϲ"),
+
+    ns("μητηρ", "μηρ", "μητηρ"),
+    ns("μητηρ", "μρϲ", "μητροϲ"),
+    ns("μητηρ", "μρι", "μητρι"),
+    ns("μητηρ", "μρα", "μητερα"),
+
+    ns("ϲωτηρ", "ϲηρ", "ϲωτηρ"),
+    ns("ϲωτηρ", "ϲρϲ", "ϲωτηροϲ"),
+    ns("ϲωτηρ", "ϲρι", "ϲωτηρι"),
+    ns("ϲωτηρ", "ϲρα", "ϲωτηρα"),
+
+    ns("ϲταυροϲ", "ϲτϲ", "ϲταυροϲ"),
+    ns("ϲταυροϲ", "ϲτροϲ", "ϲταυροϲ"),
+    ns("ϲταυροϲ", "ϲτρου", "ϲταυρου"),
+    ns("ϲταυροϲ", "ϲτρω", "ϲταυρω"),
+    ns("ϲταυροϲ", "ϲτρον", "ϲταυρον"),
+
+    ns("ιϲραηλ", "ιηλ", "ιϲραηλ"),
+    ns("ιϲραηλ", "ιϲλ", "ιϲραηλ"),
+    ns("δαυιδ", "δαδ", "δαυιδ"),
+    ns("δαυιδ", "δδ", "δαυιδ"),
+    ns("ιερουϲαλημ", "ιλημ", "ιερουϲαλημ"),
+    ns("ιερουϲαλημ", "ιηλμ", "ιερουϲαλημ"),
+];
+
+/// The contracted forms that are also common words, which are only taken
+/// as nomina sacra when the manuscript marks them, see
+/// [`expand_marked_word`].
+pub const MARKED: &[NomenSacrum] = &[
+    ns("υιοϲ", "υϲ", "υιοϲ"),
+    ns("κυριοϲ", "κω", "κυριω"),
+    ns("ανθρωποϲ", "ανω", "ανθρωπω"),
+    ns("πατηρ", "περ", "πατερ"),
+];
+
+/// The full form of the contracted form `word`, if it is one.
+/// # Example :
+/// ```
+/// use gnt_tools::nomina_sacra::expand_word;
+///
+/// assert_eq!(expand_word("πνϲ"), Some("πνευματοϲ"));
+/// assert_eq!(expand_word("ειϲ"), None);
+/// ```
+pub fn expand_word(word: &str) -> Option<&'static str> {
+    TABLE.iter().find(|n| n.contracted == word).map(|n| n.full)
+}
+
+/// Same as [`expand_word`] for a word marked as a nomen sacrum by the
+/// manuscript (e.g. `|υϲ|`), which may also be one of the [`MARKED`]
+/// forms.
+/// # Example :
+/// ```
+/// use gnt_tools::nomina_sacra::{expand_marked_word, expand_word};
+///
+/// assert_eq!(expand_word("υϲ"), None);
+/// assert_eq!(expand_marked_word("υϲ"), Some("υιοϲ"));
+/// ```
+pub fn expand_marked_word(word: &str) -> Option<&'static str> {
+    expand_word(word).or_else(|| {
+        MARKED.iter().find(|n| n.contracted == word).map(|n| n.full)
+    })
+}
+
+/// The contracted form of the full form `word`, if it has one.
+/// # Example :
+/// ```
+/// use gnt_tools::nomina_sacra::contract_word;
+///
+/// assert_eq!(contract_word("ιϲραηλ"), Some("ιηλ"));
+/// assert_eq!(contract_word("λογοϲ"), None);
+/// ```
+pub fn contract_word(word: &str) -> Option<&'static str> {
+    TABLE.iter().find(|n| n.full == word).map(|n| n.contracted)
+}
+
+/// Replaces each word of `core` that is a contracted nomen sacrum by its
+/// full form.
+///
+/// The letters of a replaced word all get the source span of the whole
+/// contracted word.
+pub fn expand(core: &CoreText) -> CoreText {
+    replace_words(core, expand_word)
+}
+
+/// Replaces each word of `core` that has a nomen sacrum form by this form.
+///
+/// The letters of a replaced word all get the source span of the whole
+/// full word.
+pub fn contract(core: &CoreText) -> CoreText {
+    replace_words(core, contract_word)
+}
+
+fn replace_words(core: &CoreText, replace: fn(&str) -> Option<&'static str>)
+    -> CoreText {
+
+    let mut out = core.empty_like();
+
+    for (letters, word) in core.words() {
+        match replace(word) {
+            Some(new) => {
+                // A word of a core text always has a letter, hence a span.
+                let span = core.source_span(letters).unwrap();
+                out.push_word(new.chars().map(|c| (c, span)));
+            }
+            None => out.push_word(word.chars().zip(
+                                      core.spans()[letters].iter().copied())),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_text;
+
+    #[test]
+    fn test_table_round_trip() {
+        for n in TABLE {
+            assert!(expand_word(n.contracted).is_some(), "{}", n.contracted);
+            assert_eq!(contract_word(expand_word(n.contracted).unwrap())
+                           .and_then(expand_word),
+                       Some(n.full));
+            assert_eq!(core_text(n.full).unwrap(), n.full);
+            assert_eq!(core_text(n.contracted).unwrap(), n.contracted);
+        }
+    }
+
+    #[test]
+    fn test_expand_keeps_spans() {
+        let s = "εἰς τὸν |ουνον| (θυ)";
+        let core = core_text(s).unwrap();
+        let expanded = expand(&core);
+
+        assert_eq!(expanded, "ειϲτονουρανονθεου");
+        assert_eq!(expanded.word_count(), 4);
+
+        let span = expanded.source_span(6..13).unwrap();
+        assert_eq!(&s[span.bytes()], "ουνον");
+        assert_eq!(expanded.span(13), expanded.span(16));
+        assert_eq!(&s[expanded.span(13).unwrap().bytes()], "θυ");
+    }
+
+    #[test]
+    fn test_contract_only_whole_words() {
+        let core = core_text("ἐν τῷ ὀνόματι τοῦ πατρὸς καὶ τοῦ υἱοῦ, πατρίς").unwrap();
+
+        assert_eq!(contract(&core), "εντωονοματιτουπρϲκαιτουυυπατριϲ");
+        assert_eq!(contract(&core).word_count(), core.word_count());
+    }
+
+    #[test]
+    fn test_common_words() {
+        // ὗς, the sow of 2 Pet 2:22, is not the son.
+        let core = core_text("ὗς λουσαμένη").unwrap();
+        assert_eq!(expand(&core), core.as_str());
+        assert_eq!(contract(&core_text("υἱός").unwrap()), "υιοϲ");
+
+        // Cos, in Acts 21:1, is not the Lord.
+        let core = core_text("ἤλθομεν εἰς τὴν Κῶ").unwrap();
+        assert_eq!(expand(&core), core.as_str());
+        assert_eq!(expand_word("κω"), None);
+        assert_eq!(expand_marked_word("κω"), Some("κυριω"));
+
+        for n in MARKED {
+            assert_eq!(expand_word(n.contracted), None);
+            assert_eq!(expand_marked_word(n.contracted), Some(n.full));
+        }
+    }
+}
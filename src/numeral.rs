@@ -0,0 +1,302 @@
+//! Greek numerals : letters standing for numbers (e.g. `ιβʹ` for 12).
+//!
+//! [`tokenize`](crate::token::tokenize) recognises the numerals marked in
+//! the source (with a keraia, a lower keraia or an overline) as
+//! [`Token::Numeral`](crate::token::Token::Numeral). Since a witness may
+//! write `ιβ` where another spells `δωδεκα`, [`letter_numerals`] replaces
+//! the spelled out cardinal numbers of a core text by their numeral, so
+//! both compare equal.
+//! # Example :
+//! ```
+//! use gnt_tools::core_text;
+//! use gnt_tools::numeral::letter_numerals;
+//!
+//! let printed = core_text("οἱ δώδεκα").unwrap();
+//! let uncial = core_text("οι ιβʹ").unwrap();
+//!
+//! assert_eq!(letter_numerals(&printed).as_str(), uncial.as_str());
+//! ```
+
+use crate::CoreText;
+
+/// The lower keraia `͵`, put before a letter to multiply it by 1000.
+pub const LOWER_KERAIA: char = '\u{0375}';
+
+/// Whether `c` is a keraia `ʹ`, put after the letters of a numeral.
+pub fn is_keraia(c: char) -> bool {
+    c == '\u{0374}' || c == '\u{02B9}'
+}
+
+/// The numeric value of the letter `c`, digamma, stigma, koppa and sampi
+/// included.
+/// # Example :
+/// ```
+/// use gnt_tools::numeral::letter_value;
+///
+/// assert_eq!(letter_value('ϛ'), Some(6));
+/// assert_eq!(letter_value('Ϟ'), Some(90));
+/// assert_eq!(letter_value('ϲ'), Some(200));
+/// assert_eq!(letter_value('ͺ'), None);
+/// ```
+pub fn letter_value(c: char) -> Option<u32> {
+    Some(match c {
+        'α' | 'Α' => 1,
+        'β' | 'Β' => 2,
+        'γ' | 'Γ' => 3,
+        'δ' | 'Δ' => 4,
+        'ε' | 'Ε' => 5,
+        'ϛ' | 'Ϛ' | 'ϝ' | 'Ϝ' => 6,
+        'ζ' | 'Ζ' => 7,
+        'η' | 'Η' => 8,
+        'θ' | 'Θ' => 9,
+        'ι' | 'Ι' => 10,
+        'κ' | 'Κ' => 20,
+        'λ' | 'Λ' => 30,
+        'μ' | 'Μ' => 40,
+        'ν' | 'Ν' => 50,
+        'ξ' | 'Ξ' => 60,
+        'ο' | 'Ο' => 70,
+        'π' | 'Π' => 80,
+        'ϟ' | 'Ϟ' | 'ϙ' | 'Ϙ' => 90,
+        'ρ' | 'Ρ' => 100,
+        'σ' | 'ς' | 'ϲ' | 'Σ' | 'Ϲ' => 200,
+        'τ' | 'Τ' => 300,
+        'υ' | 'Υ' => 400,
+        'φ' | 'Φ' => 500,
+        'χ' | 'Χ' => 600,
+        'ψ' | 'Ψ' => 700,
+        'ω' | 'Ω' => 800,
+        'ϡ' | 'Ϡ' | 'ͳ' | 'Ͳ' => 900,
+        _ => return None,
+    })
+}
+
+/// The value of a sequence of letter values, if it is a well formed
+/// numeral below 1000 : at most one hundred, then one ten, then one unit.
+pub(crate) fn parse_values(values: impl IntoIterator<Item = u32>) -> Option<u32> {
+    let mut total = 0;
+    // The magnitude the next value must be below.
+    let mut below = 1000;
+
+    for v in values {
+        let magnitude = [100, 10, 1].into_iter().find(|m| v >= *m)?;
+        if magnitude >= below {
+            return None;
+        }
+        below = magnitude;
+        total += v;
+    }
+
+    (total > 0).then_some(total)
+}
+
+/// The value of the numeral written with `letters`, if it is a well formed
+/// numeral below 1000.
+/// # Example :
+/// ```
+/// use gnt_tools::numeral::parse_letters;
+///
+/// assert_eq!(parse_letters("χξϛ"), Some(666));
+/// assert_eq!(parse_letters("ιβ"), Some(12));
+/// assert_eq!(parse_letters("κϲ"), None);
+/// ```
+pub fn parse_letters(letters: &str) -> Option<u32> {
+    let values : Option<Vec<u32>> = letters.chars().map(letter_value).collect();
+    parse_values(values?)
+}
+
+/// The numeral of `value` in core form, for `value` in `1..1000`.
+///
+/// Six is written with stigma `ϛ`, ninety with koppa `ϟ` and nine hundred
+/// with sampi `ϡ`.
+/// # Example :
+/// ```
+/// use gnt_tools::numeral::to_letters;
+///
+/// assert_eq!(to_letters(153).as_deref(), Some("ρνγ"));
+/// assert_eq!(to_letters(1000), None);
+/// ```
+pub fn to_letters(value: u32) -> Option<String> {
+    const UNITS: [char; 9] = ['α', 'β', 'γ', 'δ', 'ε', 'ϛ', 'ζ', 'η', 'θ'];
+    const TENS: [char; 9] = ['ι', 'κ', 'λ', 'μ', 'ν', 'ξ', 'ο', 'π', 'ϟ'];
+    const HUNDREDS: [char; 9] = ['ρ', 'ϲ', 'τ', 'υ', 'φ', 'χ', 'ψ', 'ω', 'ϡ'];
+
+    if !(1..1000).contains(&value) {
+        return None;
+    }
+
+    Some([(HUNDREDS, value / 100), (TENS, value / 10 % 10), (UNITS, value % 10)]
+             .iter()
+             .filter(|(_, digit)| *digit > 0)
+             .map(|(letters, digit)| letters[*digit as usize - 1])
+             .collect())
+}
+
+const CARDINALS: &[(&str, u32)] = &[
+    ("μια", 1), ("μιαϲ", 1), ("μιαν", 1),
+    ("ενοϲ", 1), ("ενι", 1), ("ενα", 1),
+    ("δυο", 2), ("δυϲι", 2), ("δυϲιν", 2),
+    ("τρειϲ", 3), ("τρια", 3), ("τριων", 3), ("τριϲι", 3), ("τριϲιν", 3),
+    ("τεϲϲαρεϲ", 4), ("τεϲϲαρα", 4), ("τεϲϲαραϲ", 4), ("τεϲϲαρων", 4),
+    ("τεϲϲαραϲι", 4), ("τεϲϲαραϲιν", 4),
+    ("πεντε", 5), ("επτα", 7), ("οκτω", 8), ("εννεα", 9),
+    ("δεκα", 10), ("ενδεκα", 11), ("δωδεκα", 12), ("δεκαδυο", 12),
+    ("δεκατρειϲ", 13), ("δεκατεϲϲαρεϲ", 14), ("δεκαπεντε", 15),
+    ("δεκαεξ", 16), ("δεκαεπτα", 17), ("δεκαοκτω", 18), ("δεκαεννεα", 19),
+    ("εικοϲι", 20), ("εικοϲιν", 20), ("τριακοντα", 30),
+    ("τεϲϲερακοντα", 40), ("τεϲϲαρακοντα", 40), ("πεντηκοντα", 50),
+    ("εξηκοντα", 60), ("εβδομηκοντα", 70), ("ογδοηκοντα", 80),
+    ("ενενηκοντα", 90), ("εκατον", 100),
+];
+
+const DECLINED_CARDINALS: &[(&str, u32)] = &[
+    ("διακοϲι", 200), ("τριακοϲι", 300), ("τετρακοϲι", 400),
+    ("πεντακοϲι", 500), ("εξακοϲι", 600), ("επτακοϲι", 700),
+    ("οκτακοϲι", 800), ("ενακοϲι", 900),
+];
+
+const ENDINGS: &[&str] = &["οι", "αι", "α", "ων", "οιϲ", "αιϲ", "ουϲ", "αϲ"];
+
+/// The value of the cardinal number `word`, given in core form.
+///
+/// `ειϲ`, `εν` and `εξ` are left out since they are far more often the
+/// prepositions than the numbers one and six. [`letter_numerals`] still
+/// takes `εξ` as six after the tens of a compound number.
+/// # Example :
+/// ```
+/// use gnt_tools::numeral::cardinal_value;
+///
+/// assert_eq!(cardinal_value("δωδεκα"), Some(12));
+/// assert_eq!(cardinal_value("εξακοϲιοι"), Some(600));
+/// assert_eq!(cardinal_value("ειϲ"), None);
+/// ```
+pub fn cardinal_value(word: &str) -> Option<u32> {
+    CARDINALS.iter()
+             .find(|(w, _)| *w == word)
+             .or_else(|| DECLINED_CARDINALS.iter().find(|(stem, _)| {
+                 word.strip_prefix(stem).is_some_and(|e| ENDINGS.contains(&e))
+             }))
+             .map(|(_, v)| *v)
+}
+
+/// The articles, and the endings of the genitive, after which `εξ` is the
+/// preposition : `εικοϲι εξ αυτων`.
+const ARTICLES: &[&str] = &[
+    "ο", "η", "το", "οι", "αι", "τα", "του", "τηϲ", "των", "τω", "τη",
+    "τοιϲ", "ταιϲ", "τον", "την", "τουϲ", "ταϲ",
+];
+const GENITIVE_ENDINGS: &[&str] = &["ου", "ων", "ηϲ", "αϲ", "οϲ"];
+
+/// The value of `word` in a compound number whose previous word is worth
+/// `previous`, and followed by `next`. `εξ` is six only after a tens word
+/// (`εξηκοντα εξ`), and not before an article or a genitive.
+fn compound_value(word: &str, previous: u32, next: Option<&str>) -> Option<u32> {
+    match word {
+        "εξ" => {
+            let tens = (20..100).contains(&previous) && previous.is_multiple_of(10);
+            let preposition = next.is_some_and(|n| {
+                ARTICLES.contains(&n) || GENITIVE_ENDINGS.iter().any(|e| n.ends_with(e))
+            });
+            (tens && !preposition).then_some(6)
+        }
+        _ => cardinal_value(word),
+    }
+}
+
+/// Replaces each spelled out cardinal number of `core` below 1000 by its
+/// numeral (see [`to_letters`]).
+///
+/// Consecutive cardinals of decreasing magnitude, as in `εξακοϲιοι
+/// εξηκοντα εξ`, are taken as one number. The letters of a replaced number all get
+/// the source span of the whole number.
+pub fn letter_numerals(core: &CoreText) -> CoreText {
+    let mut out = core.empty_like();
+    let words : Vec<_> = core.words().collect();
+    let mut i = 0;
+
+    while i < words.len() {
+        let mut total = 0;
+        // The value of the last word.
+        let mut last = 0;
+        // The magnitude the next value must be below.
+        let mut below = 1000;
+        let mut j = i;
+        while let Some(v) = words.get(j).and_then(|(_, w)| {
+            compound_value(w, last, words.get(j + 1).map(|(_, n)| *n))
+        }) {
+            if v >= below {
+                break;
+            }
+            total += v;
+            last = v;
+            below = [1, 10, 100].into_iter().find(|m| v / m % 10 > 0).unwrap();
+            j += 1;
+        }
+
+        match to_letters(total).filter(|_| j > i) {
+            Some(letters) => {
+                let span = core.source_span(words[i].0.start..words[j - 1].0.end)
+                               .unwrap();
+                out.push_word(letters.chars().map(|c| (c, span)));
+                i = j;
+            }
+            None => {
+                let (letters, word) = &words[i];
+                out.push_word(word.chars().zip(
+                    core.spans()[letters.clone()].iter().copied()));
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_text;
+
+    #[test]
+    fn test_letters_round_trip() {
+        for v in 1..1000 {
+            assert_eq!(parse_letters(&to_letters(v).unwrap()), Some(v));
+        }
+    }
+
+    #[test]
+    fn test_parse_ill_formed() {
+        assert_eq!(parse_letters(""), None);
+        assert_eq!(parse_letters("βι"), None);
+        assert_eq!(parse_letters("ιι"), None);
+        assert_eq!(parse_letters("ρρ"), None);
+        assert_eq!(parse_letters("λογοϲ"), None);
+    }
+
+    #[test]
+    fn test_six_and_prepositions() {
+        let core = letter_numerals(&core_text("ἐξ αὐτῶν εἰς ἕξ ἑξήκοντα ἕξ").unwrap());
+
+        assert_eq!(core, "εξαυτωνειϲεξξϛ");
+
+        // `εξ` after a number is still the preposition, unless after tens.
+        let core = letter_numerals(&core_text("δέκα ἐξ αὐτῶν").unwrap());
+        assert_eq!(core, "ιεξαυτων");
+        let core = letter_numerals(&core_text("εἴκοσι ἐξ αὐτῶν").unwrap());
+        assert_eq!(core, "κεξαυτων");
+        let core = letter_numerals(&core_text("ἑξακόσιοι ἐξ ἡμῶν").unwrap());
+        assert_eq!(core, "χεξημων");
+    }
+
+    #[test]
+    fn test_letter_numerals() {
+        let s = "ἦμεν δὲ αἱ πᾶσαι ψυχαὶ διακόσιαι ἑβδομήκοντα ἕξ. δύο δώδεκα";
+        let core = letter_numerals(&core_text(s).unwrap());
+
+        assert_eq!(core, "ημενδεαιπαϲαιψυχαιϲοϛβιβ");
+        assert_eq!(core.word_count(), 8);
+
+        let span = core.source_span(18..21).unwrap();
+        assert_eq!(&s[span.bytes()], "διακόσιαι ἑβδομήκοντα ἕξ");
+    }
+}
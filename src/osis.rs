@@ -0,0 +1,290 @@
+//! Import and export of OSIS XML documents.
+//!
+//! The verses are read whether they are containers
+//! (`<verse osisID="Luke.12.16">…</verse>`) or milestones
+//! (`<verse sID="Luke.12.16" osisID="Luke.12.16"/>…<verse eID="Luke.12.16"/>`).
+//! The notes and titles are left out of the text of the verses, and the
+//! whitespace runs are taken as a single space.
+//! # Example :
+//! ```
+//! use gnt_tools::osis;
+//!
+//! let xml = r#"<osis><osisText osisIDWork="SBLGNT">
+//!   <div type="book" osisID="Luke"><chapter osisID="Luke.12">
+//!     <verse osisID="Luke.12.16">Εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς λέγων·</verse>
+//!   </chapter></div>
+//! </osisText></osis>"#;
+//!
+//! let books = osis::read(xml).unwrap();
+//! let verse = books[0].verse(12, 16).unwrap();
+//!
+//! assert_eq!(verse.core_text().unwrap(), "ειπενδεπαραβοληνπροϲαυτουϲλεγων");
+//!
+//! let core = osis::write_core(&books, "SBLGNT").unwrap();
+//! assert!(core.contains(
+//!     r#"<verse osisID="Luke.12.16">ειπενδεπαραβοληνπροϲαυτουϲλεγων</verse>"#));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+
+use roxmltree::{Document, Node};
+
+use crate::reference::{Book, Reference};
+use crate::verse::{push_text, push_verse, BookText, Verse};
+use crate::UnhandledGreekChar;
+
+/// An OSIS document that cannot be read or written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OsisError {
+    /// The document is not well-formed XML.
+    Xml(String),
+    /// An `osisID` that is not the reference of a verse.
+    OsisId(String),
+    /// A book title that is not one of the New Testament.
+    Book(String),
+    /// A verse with an unhandled character, when writing its core text.
+    Unhandled(UnhandledGreekChar),
+}
+
+impl fmt::Display for OsisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OsisError::Xml(e) => write!(f, "invalid OSIS document : {e}"),
+            OsisError::OsisId(id) => write!(f, "'{id}' is not a verse osisID"),
+            OsisError::Book(title) => {
+                write!(f, "'{title}' is not a New Testament book")
+            }
+            OsisError::Unhandled(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for OsisError {}
+
+impl From<UnhandledGreekChar> for OsisError {
+    fn from(e: UnhandledGreekChar) -> OsisError {
+        OsisError::Unhandled(e)
+    }
+}
+
+/// The elements whose text is not part of the verse.
+const SKIPPED: [&str; 2] = ["note", "title"];
+
+/// Reads the verses of the OSIS document `xml`. The span of a verse is
+/// the byte range of its text in `xml`, from its first letter to its last,
+/// and its pieces map its text back to `xml`, see
+/// [`Verse::source_range`].
+pub fn read(xml: &str) -> Result<Vec<BookText>, OsisError> {
+    let doc = Document::parse(xml).map_err(|e| OsisError::Xml(e.to_string()))?;
+    let mut reader = Reader { xml, books: Vec::new(), current: None };
+
+    reader.walk(doc.root())?;
+    reader.finish();
+
+    Ok(reader.books)
+}
+
+struct Reader<'x> {
+    xml: &'x str,
+    books: Vec<BookText>,
+    /// The verse being read, with its reference.
+    current: Option<(Reference, Verse)>,
+}
+
+impl Reader<'_> {
+    fn walk(&mut self, node: Node) -> Result<(), OsisError> {
+        for child in node.children() {
+            if is(child, "verse") {
+                if child.has_attribute("eID") {
+                    self.finish();
+                } else if let Some(id) = child.attribute("osisID") {
+                    self.finish();
+                    let (r, last) = osis_id(id)?;
+                    self.current = Some((r, Verse {
+                        number: r.verse,
+                        last,
+                        text: String::new(),
+                        span: 0..0,
+                        pieces: Vec::new(),
+                    }));
+                    if child.has_children() {
+                        self.walk(child)?;
+                        self.finish();
+                    }
+                } else {
+                    self.walk(child)?;
+                }
+            } else if child.is_text() {
+                self.text(child);
+            } else if !SKIPPED.iter().any(|s| is(child, s)) {
+                self.walk(child)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn text(&mut self, node: Node) {
+        let Some((_, verse)) = &mut self.current else {
+            return;
+        };
+        let range = node.range();
+        push_text(verse, node.text().unwrap_or_default(), &self.xml[range.clone()],
+                  range.start);
+    }
+
+    fn finish(&mut self) {
+        if let Some((r, mut verse)) = self.current.take() {
+            verse.text.truncate(verse.text.trim_end().len());
+            push_verse(&mut self.books, r.book, r.chapter, verse);
+        }
+    }
+}
+
+fn is(node: Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+/// The verse of `id`, e.g. `Luke.12.16`, and the number of its last verse.
+/// A list of verses, e.g. `Luke.12.16 Luke.12.17`, must follow each other
+/// in a chapter. The work prefix (`SBLGNT:`) is dropped.
+fn osis_id(id: &str) -> Result<(Reference, u32), OsisError> {
+    let error = || OsisError::OsisId(id.to_string());
+    let references = id.split_whitespace()
+                       .map(|r| r.rsplit_once(':').map_or(r, |(_, r)| r)
+                                 .parse::<Reference>()
+                                 .map_err(|_| error()))
+                       .collect::<Result<Vec<_>, _>>()?;
+    let first = *references.first().ok_or_else(error)?;
+    let in_order = references.iter().enumerate().all(|(i, r)| {
+        (r.book, r.chapter) == (first.book, first.chapter)
+        && r.verse == first.verse + i as u32
+    });
+    if !in_order {
+        return Err(error());
+    }
+
+    Ok((first, first.verse + references.len() as u32 - 1))
+}
+
+/// Writes `books` as an OSIS document of the work `work`.
+pub fn write(books: &[BookText], work: &str) -> Result<String, OsisError> {
+    write_with(books, work, |verse| Ok(verse.text.clone()))
+}
+
+/// Writes the core text of each verse of `books` as an OSIS document of
+/// the work `work`, with the references of the verses.
+pub fn write_core(books: &[BookText], work: &str) -> Result<String, OsisError> {
+    write_with(books, work, |verse| Ok(verse.core_text()?.into_string()))
+}
+
+fn write_with(books: &[BookText], work: &str,
+              text: impl Fn(&Verse) -> Result<String, OsisError>)
+    -> Result<String, OsisError> {
+
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <osis xmlns=\"http://www.bibletechnologies.net/2003/OSIS/namespace\">\n");
+    let work = escape(work);
+    writeln!(out, "<osisText osisIDWork=\"{work}\" xml:lang=\"grc\">").unwrap();
+    writeln!(out, "<header><work osisWork=\"{work}\"/></header>").unwrap();
+
+    for book in books {
+        let osis = book.book()
+                       .map(Book::osis)
+                       .ok_or_else(|| OsisError::Book(book.title.clone()))?;
+        writeln!(out, "<div type=\"book\" osisID=\"{osis}\">").unwrap();
+        for chapter in &book.chapters {
+            writeln!(out, "<chapter osisID=\"{osis}.{}\">", chapter.number).unwrap();
+            for verse in &chapter.verses {
+                let ids : Vec<String> = (verse.number..=verse.last)
+                    .map(|v| format!("{osis}.{}.{v}", chapter.number))
+                    .collect();
+                writeln!(out, "<verse osisID=\"{}\">{}</verse>",
+                         ids.join(" "), escape(&text(verse)?)).unwrap();
+            }
+            out.push_str("</chapter>\n");
+        }
+        out.push_str("</div>\n");
+    }
+    out.push_str("</osisText>\n</osis>\n");
+
+    Ok(out)
+}
+
+/// `s` with the special characters of XML escaped.
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+     .replace('<', "&lt;")
+     .replace('>', "&gt;")
+     .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::book_words;
+
+    const MILESTONES: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osis xmlns="http://www.bibletechnologies.net/2003/OSIS/namespace">
+<osisText osisIDWork="SBLGNT">
+<div type="book" osisID="Matt">
+<chapter osisID="Matt.1">
+<title>ΚΑΤΑ ΜΑΘΘΑΙΟΝ</title>
+<verse sID="Matt.1.1" osisID="SBLGNT:Matt.1.1"/>Βίβλος γενέσεως
+  Ἰησοῦ Χριστοῦ<note>Ἰησοῦ</note> &amp; υἱοῦ Δαυὶδ<verse eID="Matt.1.1"/>
+<verse sID="Matt.1.2" osisID="Matt.1.2"/>Ἀβραὰμ ἐγέννησεν<verse eID="Matt.1.2"/>
+</chapter>
+</div>
+<div type="book" osisID="Mark"><chapter osisID="Mark.1">
+<verse osisID="Mark.1.1">Ἀρχὴ τοῦ εὐαγγελίου</verse>
+</chapter></div>
+</osisText>
+</osis>"#;
+
+    #[test]
+    fn test_read() {
+        let books = read(MILESTONES).unwrap();
+
+        assert_eq!(books.len(), 2);
+        assert_eq!(books[0].book(), Some(Book::Matthew));
+        let verse = books[0].verse(1, 1).unwrap();
+        assert_eq!(verse.text, "Βίβλος γενέσεως Ἰησοῦ Χριστοῦ & υἱοῦ Δαυὶδ");
+        assert!(MILESTONES[verse.span.clone()].starts_with("Βίβλος"));
+        assert!(MILESTONES[verse.span.clone()].ends_with("Δαυὶδ"));
+        // The words are found in the source, whatever the markup.
+        let words = book_words(Book::Matthew, &books[0]).unwrap();
+        let found : Vec<&str> = words.iter().map(|w| &MILESTONES[w.span.clone()]).collect();
+        assert_eq!(found, ["Βίβλος", "γενέσεως", "Ἰησοῦ", "Χριστοῦ", "υἱοῦ", "Δαυὶδ",
+                           "Ἀβραὰμ", "ἐγέννησεν"]);
+        assert_eq!(books[1].verse(1, 1).unwrap().text, "Ἀρχὴ τοῦ εὐαγγελίου");
+
+        assert!(matches!(read("<osis>"), Err(OsisError::Xml(_))));
+        assert_eq!(read("<verse osisID=\"Tob.1.1\">x</verse>"),
+                   Err(OsisError::OsisId("Tob.1.1".to_string())));
+
+        // A verse bridging several ones.
+        let books = read("<verse osisID=\"Matt.1.2 Matt.1.3\">x</verse>").unwrap();
+        assert_eq!(books[0].verse(1, 2).unwrap().last, 3);
+        assert!(write(&books, "W").unwrap().contains("osisID=\"Matt.1.2 Matt.1.3\""));
+        assert!(read("<verse osisID=\"Matt.1.2 Matt.1.4\">x</verse>").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let books = read(MILESTONES).unwrap();
+        let xml = write(&books, "SBLGNT").unwrap();
+
+        let again = read(&xml).unwrap();
+        assert_eq!(again.len(), books.len());
+        for (a, b) in again.iter().zip(&books) {
+            let a : Vec<_> = a.verses().map(|(c, v)| (c, v.number, &v.text)).collect();
+            let b : Vec<_> = b.verses().map(|(c, v)| (c, v.number, &v.text)).collect();
+            assert_eq!(a, b);
+        }
+        assert!(xml.contains("<verse osisID=\"Matt.1.1\">Βίβλος γενέσεως Ἰησοῦ \
+                              Χριστοῦ &amp; υἱοῦ Δαυὶδ</verse>"));
+    }
+}
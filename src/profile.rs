@@ -0,0 +1,377 @@
+use unicode_normalization::char::compose;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::removed::removed_chars_with;
+use crate::table::{self, Mapping, Marks};
+use crate::{base_letter, is_dropped_greek, is_unassigned_greek, unhandled_panic,
+            CoreEngine, CoreText, Policy, RemovedChar, UnhandledGreekChar};
+
+const DIAERESIS: char = '\u{0308}';
+const YPOGEGRAMMENI: char = '\u{0345}';
+pub(crate) const MACRON: char = '\u{0304}';
+
+/// The rules followed by the core normalization.
+///
+/// The default profile is the one of [`core_char`](crate::core_char) :
+/// lowercase letters, lunate sigma only, no diacritics. Each rule can be
+/// changed with the builder methods.
+/// # Example :
+/// ```
+/// use gnt_tools::CoreProfile;
+///
+/// let s = "Ἰησοῦς ᾄδει Ἠσαΐου";
+///
+/// assert_eq!(CoreProfile::new().core_text(s).unwrap(),
+///            "ιηϲουϲαδειηϲαιου");
+///
+/// let profile = CoreProfile::new().keep_case(true)
+///                                 .medial_sigma(true)
+///                                 .keep_final_sigma(true)
+///                                 .keep_diaeresis(true)
+///                                 .keep_iota_subscript(true);
+///
+/// assert_eq!(profile.core_text(s).unwrap(), "ΙησουςᾳδειΗσαϊου");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CoreProfile {
+    keep_case: bool,
+    keep_final_sigma: bool,
+    medial_sigma: bool,
+    keep_diaeresis: bool,
+    keep_iota_subscript: bool,
+    suspended_nu: bool,
+}
+
+impl Default for CoreProfile {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl CoreProfile {
+    /// The profile of [`core_char`](crate::core_char).
+    pub const DEFAULT: CoreProfile = CoreProfile {
+        keep_case: false,
+        keep_final_sigma: false,
+        medial_sigma: false,
+        keep_diaeresis: false,
+        keep_iota_subscript: false,
+        suspended_nu: false,
+    };
+
+    /// Same as [`CoreProfile::DEFAULT`].
+    pub const fn new() -> Self {
+        Self::DEFAULT
+    }
+
+    /// Keeps uppercase letters instead of putting them in lowercase.
+    pub const fn keep_case(mut self, yes: bool) -> Self {
+        self.keep_case = yes;
+        self
+    }
+
+    /// Keeps final sigma `ς` instead of changing it.
+    pub const fn keep_final_sigma(mut self, yes: bool) -> Self {
+        self.keep_final_sigma = yes;
+        self
+    }
+
+    /// Outputs medial sigma `σ` (or `Σ`) instead of lunate sigma `ϲ`
+    /// (or `Ϲ`).
+    pub const fn medial_sigma(mut self, yes: bool) -> Self {
+        self.medial_sigma = yes;
+        self
+    }
+
+    /// Keeps the diaeresis, e.g. `ϊ` is not changed to `ι`.
+    ///
+    /// In a decomposed text, the combining diaeresis (U+0308) is composed
+    /// with its letter at the text level ([`CoreProfile::core_text`]) : it
+    /// is dropped by [`CoreProfile::core_char`], which sees it alone.
+    /// # Example :
+    /// ```
+    /// use gnt_tools::CoreProfile;
+    ///
+    /// let profile = CoreProfile::new().keep_diaeresis(true);
+    ///
+    /// assert_eq!(profile.core_text("Ἠσαΐου").unwrap(), "ηϲαϊου");
+    /// assert_eq!(profile.core_text("Ἠσαι\u{301}\u{308}ου").unwrap(), "ηϲαϊου");
+    /// ```
+    pub const fn keep_diaeresis(mut self, yes: bool) -> Self {
+        self.keep_diaeresis = yes;
+        self
+    }
+
+    /// Keeps the iota subscript, e.g. `ᾳ` is not changed to `α`. As with
+    /// [`CoreProfile::keep_diaeresis`], the combining ypogegrammeni
+    /// (U+0345) of a decomposed text is kept at the text level only.
+    pub const fn keep_iota_subscript(mut self, yes: bool) -> Self {
+        self.keep_iota_subscript = yes;
+        self
+    }
+
+    /// Expands the suspension of a final nu into `ν` instead of dropping it.
+    ///
+    /// The spacing marks `ˉ` and `¯` then give `ν` wherever they are, so
+    /// `παραβολὴˉ` gives `παραβολην`. The combining macron (U+0304) gives
+    /// `ν` only at the text level ([`CoreProfile::core_text`]), when it
+    /// stands over the last letter of a word and not over the previous one
+    /// too, since a macron over several letters marks a nomen sacrum.
+    /// # Example :
+    /// ```
+    /// use gnt_tools::CoreProfile;
+    ///
+    /// let profile = CoreProfile::new().suspended_nu(true);
+    ///
+    /// assert_eq!(profile.core_text("παραβολὴˉ").unwrap(), "παραβολην");
+    /// assert_eq!(profile.core_text("τη\u{304} ὥρᾳ").unwrap(), "τηνωρα");
+    /// assert_eq!(profile.core_text("κ\u{304}ς\u{304}").unwrap(), "κϲ");
+    /// ```
+    pub const fn suspended_nu(mut self, yes: bool) -> Self {
+        self.suspended_nu = yes;
+        self
+    }
+
+    /// Whether the suspension of a final nu is expanded, see
+    /// [`CoreProfile::suspended_nu`].
+    pub(crate) const fn is_suspended_nu(&self) -> bool {
+        self.suspended_nu
+    }
+
+    /// Whether the combining `mark` is kept, composed with its letter.
+    pub(crate) fn keeps_mark(&self, mark: char) -> bool {
+        mark == DIAERESIS && self.keep_diaeresis
+        || mark == YPOGEGRAMMENI && self.keep_iota_subscript
+    }
+
+    /// Same as [`core_char`](crate::core_char), following this profile.
+    #[inline]
+    pub fn core_char(&self, c: &char) -> Option<char> {
+        match self.try_core_char(c) {
+            Ok(core_c) => core_c,
+            Err(e) => unhandled_panic(e.ch),
+        }
+    }
+
+    /// Same as [`try_core_char`](crate::try_core_char), following this
+    /// profile.
+    #[inline]
+    pub fn try_core_char(&self, c: &char)
+        -> Result<Option<char>, UnhandledGreekChar> {
+        match table::lookup(*c) {
+            Some(Mapping::Letter(l, marks)) => {
+                let mut core_c = self.letter(l);
+                if self.keep_diaeresis && marks.contains(Marks::DIAERESIS) {
+                    core_c = compose(core_c, DIAERESIS).unwrap_or(core_c);
+                }
+                if self.keep_iota_subscript
+                   && marks.contains(Marks::IOTA_SUBSCRIPT) {
+                    core_c = compose(core_c, YPOGEGRAMMENI).unwrap_or(core_c);
+                }
+                Ok(Some(core_c))
+            }
+            Some(Mapping::Drop) => Ok(None),
+            Some(Mapping::Unhandled) => {
+                Err(UnhandledGreekChar { ch: *c, offset: 0 })
+            }
+            None => self.fold_core_char(c),
+        }
+    }
+
+    /// Same as [`core_text`](crate::core_text), following this profile.
+    pub fn core_text(&self, s: &str) -> Result<CoreText, UnhandledGreekChar> {
+        CoreEngine::cached(*self).core_text(s)
+    }
+
+    /// Same as [`core_text_with`](crate::core_text_with), following this
+    /// profile.
+    pub fn core_text_with(&self, s: &str, policy: Policy)
+        -> Result<CoreText, UnhandledGreekChar> {
+        CoreEngine::cached(*self).core_text_with(s, policy)
+    }
+
+    /// The [`CoreEngine`] of this profile, to normalize many texts
+    /// with it.
+    pub fn engine(&self) -> CoreEngine {
+        CoreEngine::cached(*self).clone()
+    }
+
+    /// Number of distinct profiles, one for each combination of rules.
+    pub(crate) const COUNT: usize = 1 << 6;
+
+    /// Index of the profile among the [`CoreProfile::COUNT`] ones.
+    pub(crate) const fn index(&self) -> usize {
+        self.keep_case as usize
+        | (self.keep_final_sigma as usize) << 1
+        | (self.medial_sigma as usize) << 2
+        | (self.keep_diaeresis as usize) << 3
+        | (self.keep_iota_subscript as usize) << 4
+        | (self.suspended_nu as usize) << 5
+    }
+
+    /// Same as [`removed_chars`](crate::removed_chars), following this
+    /// profile.
+    pub fn removed_chars(&self, s: &str) -> Vec<RemovedChar> {
+        removed_chars_with(s, self)
+    }
+
+    /// Same as [`CoreProfile::try_core_char`], computed from the canonical
+    /// decomposition of `c` without any table.
+    ///
+    /// This is the reference implementation the tables are checked
+    /// against. It is much slower, use [`CoreProfile::try_core_char`]
+    /// or a [`CoreEngine`] instead.
+    pub fn fold_core_char(&self, c: &char)
+        -> Result<Option<char>, UnhandledGreekChar> {
+        self.fold_char(c, |_| ())
+    }
+
+    /// Same as [`CoreProfile::fold_core_char`], calling `dropped` with
+    /// each code point of the decomposition of `c` that is not kept.
+    #[inline]
+    pub(crate) fn fold_char(&self, c: &char, mut dropped: impl FnMut(char))
+        -> Result<Option<char>, UnhandledGreekChar> {
+
+        c.nfd().try_fold(None, |core_c, i|
+
+                // The numeral signs (keraia, lower keraia) are dropped
+                // like the accents, see `token::tokenize` to read numerals.
+
+                  if let Some(l) = base_letter(i)
+                  {
+                      Ok(Some(self.letter(l)))
+                  }
+                  else if self.suspended_nu && (i == 'ˉ' || i == '¯')
+                  {
+                      Ok(Some('ν'))
+                  }
+                  else if self.keeps_mark(i)
+                  {
+                      match core_c.and_then(|l| compose(l, i)) {
+                          Some(composed) => Ok(Some(composed)),
+                          None => { dropped(i); Ok(core_c) }
+                      }
+                  }
+                  else if is_unassigned_greek(i)
+                  {
+                      Err(UnhandledGreekChar { ch: *c, offset: 0 })
+                  }
+                  else if i == ';'
+                       || !('Ͱ'..='Ͽ').contains(&i)
+                       || i == '·'
+                       || is_dropped_greek(i)
+                  {
+                      dropped(i);
+                      Ok(core_c)
+                  }
+                  else {
+                      Err(UnhandledGreekChar { ch: *c, offset: 0 })
+                  }
+                )
+    }
+
+    #[inline]
+    fn letter(&self, i: char) -> char {
+        let l = if self.keep_case {
+            i
+        } else {
+            i.to_lowercase().next().unwrap_or(i)
+        };
+
+        match l {
+            'ς' if self.keep_final_sigma => 'ς',
+            'σ' | 'ς' if self.medial_sigma => 'σ',
+            'σ' | 'ς' => 'ϲ',
+            'Σ' if self.medial_sigma => 'Σ',
+            'Σ' => 'Ϲ',
+            _ => l,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_profile_is_core_char() {
+        let s = "Εἶπεν δὲ παραβολὴν ΠΡΟΣ αὐτοὺς· ᾄσατε Ἠσαΐου ϋ";
+
+        for c in s.chars() {
+            assert_eq!(CoreProfile::default().core_char(&c), crate::core_char(&c));
+        }
+    }
+
+    #[test]
+    fn test_sigmas() {
+        let s = "Σίμων ὁ λεγόμενος";
+
+        assert_eq!(CoreProfile::new().core_text(s).unwrap(),
+                   "ϲιμωνολεγομενοϲ");
+        assert_eq!(CoreProfile::new().keep_case(true).core_text(s).unwrap(),
+                   "Ϲιμωνολεγομενοϲ");
+        assert_eq!(CoreProfile::new().medial_sigma(true).core_text(s).unwrap(),
+                   "σιμωνολεγομενοσ");
+        assert_eq!(CoreProfile::new().keep_final_sigma(true)
+                                     .core_text(s).unwrap(),
+                   "ϲιμωνολεγομενος");
+        assert_eq!(CoreProfile::new().keep_case(true)
+                                     .medial_sigma(true)
+                                     .keep_final_sigma(true)
+                                     .core_text(s).unwrap(),
+                   "Σιμωνολεγομενος");
+    }
+
+    #[test]
+    fn test_kept_marks() {
+        let profile = CoreProfile::new().keep_diaeresis(true)
+                                        .keep_iota_subscript(true);
+
+        assert_eq!(profile.core_text("ΐ ᾖ Ἠσαΐου ᾯ").unwrap(), "ϊῃηϲαϊουῳ");
+        assert_eq!(profile.keep_case(true).core_text("ᾯ").unwrap(), "ῼ");
+
+        // A diaeresis without a letter to compose with is dropped.
+        assert_eq!(profile.core_text("\u{0308}ι").unwrap(), "ι");
+        assert_eq!(profile.core_text("ι \u{0308}").unwrap(), "ι");
+        assert_eq!(profile.removed_chars("ι \u{0308}")[1].ch, DIAERESIS);
+    }
+
+    #[test]
+    fn test_kept_marks_decomposed() {
+        let profile = CoreProfile::new().keep_diaeresis(true)
+                                        .keep_iota_subscript(true);
+        let s = "ΐ ᾖ Ἠσαΐου ᾯ";
+        let nfd : String = s.nfd().collect();
+        let core = profile.core_text(&nfd).unwrap();
+
+        assert_eq!(core, "ϊῃηϲαϊουῳ");
+        assert_eq!(core.word_count(), 4);
+        // The letter and its marks come from the same span.
+        let span = core.span(5).unwrap();
+        assert_eq!(&nfd[span.bytes()], "ι\u{308}");
+        // The kept marks are not removed.
+        assert_eq!(profile.removed_chars(&nfd).len(),
+                   nfd.chars().count() - core.as_str().chars().count() - 4);
+        assert!(profile.removed_chars(&nfd).iter()
+                       .all(|r| r.ch != DIAERESIS && r.ch != YPOGEGRAMMENI));
+        assert_eq!(CoreProfile::new().core_text(&nfd).unwrap(), "ιηηϲαιουω");
+        assert_eq!(profile.removed_chars("ΐ")[0].ch, '\u{0301}');
+        assert_eq!(profile.removed_chars("\u{0308}")[0].ch, DIAERESIS);
+    }
+
+    #[test]
+    fn test_suspended_nu() {
+        let profile = CoreProfile::new().suspended_nu(true);
+        let s = "παραβολὴˉ ἐ\u{304}\nτη\u{304}\u{301} ι\u{304}η\u{304}υ\u{304} \
+                 τη\u{304}ς";
+        let core = profile.core_text(s).unwrap();
+
+        assert_eq!(CoreProfile::new().core_text(s).unwrap(),
+                   "παραβοληετηιηυτηϲ");
+        assert_eq!(core, "παραβοληνεντηνιηυτηϲ");
+        assert_eq!(profile.core_char(&'ˉ'), Some('ν'));
+        assert_eq!(&s[core.span(8).unwrap().bytes()], "ˉ");
+        assert_eq!(&s[core.span(10).unwrap().bytes()], "\u{304}");
+        assert_eq!(core.word_count(), 5);
+    }
+}
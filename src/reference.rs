@@ -0,0 +1,426 @@
+//! The books of the New Testament, and the references to their verses.
+//! # Example :
+//! ```
+//! use gnt_tools::reference::{Book, Reference, ReferenceRange};
+//!
+//! let range : ReferenceRange = "Lk 12:16-17".parse().unwrap();
+//! let verse : Reference = "Luke 12.17".parse().unwrap();
+//!
+//! assert_eq!(range.start.book, Book::Luke);
+//! assert!(range.contains(&verse));
+//! assert_eq!("ΚΑΤΑ ΛΟΥΚΑΝ".parse(), Ok(Book::Luke));
+//! assert_eq!(range.to_string(), "Luke 12:16-17");
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::core_text;
+
+/// A book of the New Testament, in the canonical order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Book {
+    Matthew,
+    Mark,
+    Luke,
+    John,
+    Acts,
+    Romans,
+    FirstCorinthians,
+    SecondCorinthians,
+    Galatians,
+    Ephesians,
+    Philippians,
+    Colossians,
+    FirstThessalonians,
+    SecondThessalonians,
+    FirstTimothy,
+    SecondTimothy,
+    Titus,
+    Philemon,
+    Hebrews,
+    James,
+    FirstPeter,
+    SecondPeter,
+    FirstJohn,
+    SecondJohn,
+    ThirdJohn,
+    Jude,
+    Revelation,
+}
+
+/// The names of a book.
+struct Names {
+    greek: &'static str,
+    sbl: &'static str,
+    osis: &'static str,
+    usfm: &'static str,
+    latin: &'static str,
+    english: &'static str,
+    /// Other spellings and abbreviations in use.
+    other: &'static [&'static str],
+}
+
+const fn names(greek: &'static str, sbl: &'static str, osis: &'static str,
+               usfm: &'static str, latin: &'static str, english: &'static str,
+               other: &'static [&'static str]) -> Names {
+    Names { greek, sbl, osis, usfm, latin, english, other }
+}
+
+/// The names of the books, in the order of [`Book::ALL`].
+const NAMES: [Names; 27] = [
+    names("ΚΑΤΑ ΜΑΘΘΑΙΟΝ", "Matt", "Matt", "MAT", "Matthaeus", "Matthew",
+          &["Mt", "ΚΑΤΑ ΜΑΤΘΑΙΟΝ"]),
+    names("ΚΑΤΑ ΜΑΡΚΟΝ", "Mark", "Mark", "MRK", "Marcus", "Mark",
+          &["Mk", "Mc"]),
+    names("ΚΑΤΑ ΛΟΥΚΑΝ", "Luke", "Luke", "LUK", "Lucas", "Luke", &["Lk", "Lc"]),
+    names("ΚΑΤΑ ΙΩΑΝΝΗΝ", "John", "John", "JHN", "Ioannes", "John",
+          &["Jn", "Jo", "ΚΑΤΑ ΙΩΑΝΗΝ"]),
+    names("ΠΡΑΞΕΙΣ ΑΠΟΣΤΟΛΩΝ", "Acts", "Acts", "ACT", "Actus Apostolorum",
+          "Acts", &["Ac", "Act", "ΠΡΑΞΕΙΣ"]),
+    names("ΠΡΟΣ ΡΩΜΑΙΟΥΣ", "Rom", "Rom", "ROM", "Ad Romanos", "Romans",
+          &["Ro", "Rm"]),
+    names("ΠΡΟΣ ΚΟΡΙΝΘΙΟΥΣ Α", "1 Cor", "1Cor", "1CO", "Ad Corinthios I",
+          "1 Corinthians", &["1Co", "1K"]),
+    names("ΠΡΟΣ ΚΟΡΙΝΘΙΟΥΣ Β", "2 Cor", "2Cor", "2CO", "Ad Corinthios II",
+          "2 Corinthians", &["2Co", "2K"]),
+    names("ΠΡΟΣ ΓΑΛΑΤΑΣ", "Gal", "Gal", "GAL", "Ad Galatas", "Galatians",
+          &["Ga"]),
+    names("ΠΡΟΣ ΕΦΕΣΙΟΥΣ", "Eph", "Eph", "EPH", "Ad Ephesios", "Ephesians",
+          &[]),
+    names("ΠΡΟΣ ΦΙΛΙΠΠΗΣΙΟΥΣ", "Phil", "Phil", "PHP", "Ad Philippenses",
+          "Philippians", &["Php", "Ph"]),
+    names("ΠΡΟΣ ΚΟΛΟΣΣΑΕΙΣ", "Col", "Col", "COL", "Ad Colossenses",
+          "Colossians", &["Kol", "ΠΡΟΣ ΚΟΛΑΣΣΑΕΙΣ"]),
+    names("ΠΡΟΣ ΘΕΣΣΑΛΟΝΙΚΕΙΣ Α", "1 Thess", "1Thess", "1TH",
+          "Ad Thessalonicenses I", "1 Thessalonians", &["1Th"]),
+    names("ΠΡΟΣ ΘΕΣΣΑΛΟΝΙΚΕΙΣ Β", "2 Thess", "2Thess", "2TH",
+          "Ad Thessalonicenses II", "2 Thessalonians", &["2Th"]),
+    names("ΠΡΟΣ ΤΙΜΟΘΕΟΝ Α", "1 Tim", "1Tim", "1TI", "Ad Timotheum I",
+          "1 Timothy", &["1Ti", "1T"]),
+    names("ΠΡΟΣ ΤΙΜΟΘΕΟΝ Β", "2 Tim", "2Tim", "2TI", "Ad Timotheum II",
+          "2 Timothy", &["2Ti", "2T"]),
+    names("ΠΡΟΣ ΤΙΤΟΝ", "Titus", "Titus", "TIT", "Ad Titum", "Titus",
+          &["Tit", "Tt"]),
+    names("ΠΡΟΣ ΦΙΛΗΜΟΝΑ", "Phlm", "Phlm", "PHM", "Ad Philemonem", "Philemon",
+          &["Phm"]),
+    names("ΠΡΟΣ ΕΒΡΑΙΟΥΣ", "Heb", "Heb", "HEB", "Ad Hebraeos", "Hebrews",
+          &["H"]),
+    names("ΙΑΚΩΒΟΥ", "Jas", "Jas", "JAS", "Iacobi", "James", &["Jm", "Jc"]),
+    names("ΠΕΤΡΟΥ Α", "1 Pet", "1Pet", "1PE", "Petri I", "1 Peter",
+          &["1Pe", "1P"]),
+    names("ΠΕΤΡΟΥ Β", "2 Pet", "2Pet", "2PE", "Petri II", "2 Peter",
+          &["2Pe", "2P"]),
+    names("ΙΩΑΝΝΟΥ Α", "1 John", "1John", "1JN", "Ioannis I", "1 John",
+          &["1Jn", "1J"]),
+    names("ΙΩΑΝΝΟΥ Β", "2 John", "2John", "2JN", "Ioannis II", "2 John",
+          &["2Jn", "2J"]),
+    names("ΙΩΑΝΝΟΥ Γ", "3 John", "3John", "3JN", "Ioannis III", "3 John",
+          &["3Jn", "3J"]),
+    names("ΙΟΥΔΑ", "Jude", "Jude", "JUD", "Iudae", "Jude", &["Jud", "Jd"]),
+    names("ΑΠΟΚΑΛΥΨΙΣ ΙΩΑΝΝΟΥ", "Rev", "Rev", "REV", "Apocalypsis",
+          "Revelation",
+          &["Re", "Ap", "Apoc", "ΑΠΟΚΑΛΥΨΙΣ"]),
+];
+
+impl Book {
+    /// The 27 books, in the canonical order.
+    pub const ALL: [Book; 27] = [
+        Book::Matthew, Book::Mark, Book::Luke, Book::John, Book::Acts,
+        Book::Romans, Book::FirstCorinthians, Book::SecondCorinthians,
+        Book::Galatians, Book::Ephesians, Book::Philippians,
+        Book::Colossians, Book::FirstThessalonians,
+        Book::SecondThessalonians, Book::FirstTimothy, Book::SecondTimothy,
+        Book::Titus, Book::Philemon, Book::Hebrews, Book::James,
+        Book::FirstPeter, Book::SecondPeter, Book::FirstJohn,
+        Book::SecondJohn, Book::ThirdJohn, Book::Jude, Book::Revelation,
+    ];
+
+    fn names(self) -> &'static Names {
+        &NAMES[self as usize]
+    }
+
+    fn all_names(self) -> impl Iterator<Item = &'static str> {
+        let n = self.names();
+        [n.greek, n.sbl, n.osis, n.usfm, n.latin, n.english]
+            .into_iter()
+            .chain(n.other.iter().copied())
+    }
+
+    /// The Greek title of the book, e.g. `ΚΑΤΑ ΜΑΘΘΑΙΟΝ`.
+    pub fn greek_title(self) -> &'static str {
+        self.names().greek
+    }
+
+    /// The SBL abbreviation of the book, e.g. `1 Cor`.
+    pub fn sbl(self) -> &'static str {
+        self.names().sbl
+    }
+
+    /// The OSIS identifier of the book, e.g. `1Cor`.
+    pub fn osis(self) -> &'static str {
+        self.names().osis
+    }
+
+    /// The USFM identifier of the book, e.g. `1CO`.
+    pub fn usfm(self) -> &'static str {
+        self.names().usfm
+    }
+
+    /// The Latin name of the book, e.g. `Ad Corinthios I`.
+    pub fn latin(self) -> &'static str {
+        self.names().latin
+    }
+
+    /// The English name of the book, e.g. `1 Corinthians`.
+    pub fn english(self) -> &'static str {
+        self.names().english
+    }
+
+    /// The number of chapters of the book.
+    pub fn chapters(self) -> u32 {
+        const CHAPTERS: [u32; 27] = [
+            28, 16, 24, 21, 28, 16, 16, 13, 6, 6, 4, 4, 5, 3, 6, 4, 3, 1, 13,
+            5, 5, 3, 5, 1, 1, 1, 22,
+        ];
+        CHAPTERS[self as usize]
+    }
+}
+
+impl fmt::Display for Book {
+    /// Writes the SBL abbreviation of the book.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.sbl())
+    }
+}
+
+/// The form under which two names of a book compare equal : the core text
+/// of a Greek name, the lowercase letters and digits of another name.
+fn key(name: &str) -> String {
+    match core_text(name) {
+        Ok(core) if !core.is_empty() => core.into_string(),
+        _ => name.chars()
+                 .filter(char::is_ascii_alphanumeric)
+                 .map(|c| c.to_ascii_lowercase())
+                 .collect(),
+    }
+}
+
+impl FromStr for Book {
+    type Err = ParseReferenceError;
+
+    /// Reads a Greek title, in any case and with or without accents, an
+    /// SBL abbreviation, an OSIS or USFM identifier, a Latin or English
+    /// name, or a common abbreviation. Case, spaces and dots are ignored.
+    fn from_str(s: &str) -> Result<Book, ParseReferenceError> {
+        let wanted = key(s);
+
+        Book::ALL.into_iter()
+                 .find(|b| b.all_names().any(|n| key(n) == wanted))
+                 .ok_or_else(|| ParseReferenceError::new(s))
+    }
+}
+
+/// A verse of the New Testament.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reference {
+    /// The book of the verse.
+    pub book: Book,
+    /// The number of the chapter.
+    pub chapter: u32,
+    /// The number of the verse in the chapter.
+    pub verse: u32,
+}
+
+impl Reference {
+    /// The verse `verse` of the chapter `chapter` of `book`.
+    pub const fn new(book: Book, chapter: u32, verse: u32) -> Reference {
+        Reference { book, chapter, verse }
+    }
+}
+
+impl fmt::Display for Reference {
+    /// Writes e.g. `Luke 12:16`, or `3 John 14` for the books of a single
+    /// chapter.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.book.chapters() == 1 && self.chapter == 1 {
+            write!(f, "{} {}", self.book, self.verse)
+        } else {
+            write!(f, "{} {}:{}", self.book, self.chapter, self.verse)
+        }
+    }
+}
+
+impl FromStr for Reference {
+    type Err = ParseReferenceError;
+
+    /// Reads e.g. `Lk 12:16`, `Luke 12.16` or `Luke.12.16`. The chapter
+    /// of the books of a single chapter can be left out, as in `3 John 14`.
+    fn from_str(s: &str) -> Result<Reference, ParseReferenceError> {
+        let err = || ParseReferenceError::new(s);
+
+        let (book, numbers) = split_book(s.trim()).ok_or_else(err)?;
+        let book : Book = book.parse().map_err(|_| err())?;
+        let (chapter, verse) = match chapter_verse(numbers) {
+            Some(numbers) => numbers,
+            None if book.chapters() == 1 => {
+                (1, numbers.parse().map_err(|_| err())?)
+            }
+            None => return Err(err()),
+        };
+
+        Ok(Reference { book, chapter, verse })
+    }
+}
+
+/// Splits `s` into its book and its `chapter:verse` part.
+fn split_book(s: &str) -> Option<(&str, &str)> {
+    // The numbers are the digits, colons and dots at the end of `s`,
+    // without the dot separating them from the book in an OSIS reference.
+    let numbers = s.len() - s.bytes()
+                             .rev()
+                             .take_while(|b| b.is_ascii_digit() || b":.".contains(b))
+                             .count();
+    let (book, numbers) = s.split_at(numbers);
+    let numbers = numbers.trim_start_matches('.');
+
+    (!book.trim().is_empty() && !numbers.is_empty())
+        .then(|| (book.trim_end_matches(['.', ' ']), numbers))
+}
+
+/// Reads `chapter:verse` or `chapter.verse`.
+fn chapter_verse(s: &str) -> Option<(u32, u32)> {
+    let (chapter, verse) = s.split_once([':', '.'])?;
+    Some((chapter.parse().ok()?, verse.parse().ok()?))
+}
+
+/// A range of consecutive verses of a book, both ends included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReferenceRange {
+    /// The first verse of the range.
+    pub start: Reference,
+    /// The last verse of the range, in the book of `start`.
+    pub end: Reference,
+}
+
+impl ReferenceRange {
+    /// Whether `r` is in the range.
+    pub fn contains(&self, r: &Reference) -> bool {
+        (self.start..=self.end).contains(r)
+    }
+}
+
+impl From<Reference> for ReferenceRange {
+    fn from(r: Reference) -> ReferenceRange {
+        ReferenceRange { start: r, end: r }
+    }
+}
+
+impl fmt::Display for ReferenceRange {
+    /// Writes e.g. `Luke 12:16-17` or `Luke 12:16-13:2`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start)?;
+        if self.end.chapter != self.start.chapter {
+            write!(f, "-{}:{}", self.end.chapter, self.end.verse)
+        } else if self.end.verse != self.start.verse {
+            write!(f, "-{}", self.end.verse)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl FromStr for ReferenceRange {
+    type Err = ParseReferenceError;
+
+    /// Reads a [`Reference`], or a range like `Lk 12:16-17` or
+    /// `Lk 12:16-13:2`.
+    fn from_str(s: &str) -> Result<ReferenceRange, ParseReferenceError> {
+        let err = || ParseReferenceError::new(s);
+
+        let Some((start, end)) = s.split_once(['-', '–']) else {
+            return Ok(s.parse::<Reference>().map_err(|_| err())?.into());
+        };
+        let start : Reference = start.parse().map_err(|_| err())?;
+        let end = match chapter_verse(end.trim()) {
+            Some((chapter, verse)) => Reference { chapter, verse, ..start },
+            None => Reference {
+                verse: end.trim().parse().map_err(|_| err())?,
+                ..start
+            },
+        };
+
+        if end < start {
+            return Err(err());
+        }
+        Ok(ReferenceRange { start, end })
+    }
+}
+
+/// A string that is not a book, a reference or a range of references.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseReferenceError {
+    /// The string.
+    pub input: String,
+}
+
+impl ParseReferenceError {
+    fn new(input: &str) -> ParseReferenceError {
+        ParseReferenceError { input: input.to_string() }
+    }
+}
+
+impl fmt::Display for ParseReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a New Testament reference", self.input)
+    }
+}
+
+impl Error for ParseReferenceError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_book_names() {
+        for book in Book::ALL {
+            for name in book.all_names() {
+                assert_eq!(name.parse(), Ok(book), "{name}");
+            }
+        }
+
+        assert_eq!("Κατὰ Μαθθαῖον".parse(), Ok(Book::Matthew));
+        assert_eq!("1 cor.".parse(), Ok(Book::FirstCorinthians));
+        assert_eq!("JHN".parse(), Ok(Book::John));
+        assert!("Tobit".parse::<Book>().is_err());
+    }
+
+    #[test]
+    fn test_references() {
+        let r = Reference::new(Book::SecondCorinthians, 5, 17);
+
+        for s in ["2 Cor 5:17", "2Cor.5.17", "2 Corinthians 5.17", "2Co 5:17"] {
+            assert_eq!(s.parse(), Ok(r), "{s}");
+        }
+        assert_eq!(r.to_string(), "2 Cor 5:17");
+        assert!("2 Cor 5".parse::<Reference>().is_err());
+        assert_eq!("Jude 3".parse(), Ok(Reference::new(Book::Jude, 1, 3)));
+        assert_eq!(Reference::new(Book::Philemon, 1, 6).to_string(), "Phlm 6");
+        assert!("Luke".parse::<Reference>().is_err());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let range : ReferenceRange = "Lk 12:16-13:2".parse().unwrap();
+
+        assert_eq!(range.end, Reference::new(Book::Luke, 13, 2));
+        assert!(range.contains(&Reference::new(Book::Luke, 12, 59)));
+        assert!(!range.contains(&Reference::new(Book::Luke, 13, 3)));
+        assert_eq!(range.to_string(), "Luke 12:16-13:2");
+        assert_eq!("Mt 1:1".parse::<ReferenceRange>().unwrap().to_string(),
+                   "Matt 1:1");
+        assert!("Lk 12:17-16".parse::<ReferenceRange>().is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{is_combining, CoreProfile, Policy};
+
+/// Why a code point was dropped by the core normalization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RemovedCategory {
+    /// Acute, grave or circumflex accent, combining or spacing.
+    Accent,
+    /// Smooth or rough breathing, or koronis.
+    Breathing,
+    /// Diaeresis, combining or spacing.
+    Diaeresis,
+    /// Iota subscript (ypogegrammeni).
+    IotaSubscript,
+    /// Punctuation, including Greek `;` and `·`, and editorial brackets.
+    Punctuation,
+    /// Any digit, e.g. the verse numbers.
+    Digit,
+    /// The keraia `ʹ` and lower keraia `͵` marking Greek numerals.
+    NumeralSign,
+    /// Characters used to encode nomina sacra : `|`, `(`, `)` and the
+    /// combining overline.
+    NominaSacraMarker,
+    /// `ˉ`, `¯` and the combining macron, often standing for a final nu.
+    Macron,
+    /// A letter of the Latin script.
+    Latin,
+    /// Any whitespace, including line breaks.
+    Whitespace,
+    /// A character of the Greek block that is not handled
+    /// (see [`UnhandledGreekChar`](crate::UnhandledGreekChar)).
+    Unhandled,
+    /// Anything else.
+    Other,
+}
+
+/// A code point that the core normalization dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RemovedChar {
+    /// The dropped code point. When the source character is decomposed
+    /// by NFD (e.g. `ὲ`), this is the dropped component (e.g. U+0300).
+    pub ch: char,
+    /// Byte offset in the source of the character `ch` belongs to.
+    pub offset: usize,
+    /// Index, counted in `char`s, of the source character `ch` belongs to.
+    pub char_index: usize,
+    /// Why `ch` was dropped.
+    pub category: RemovedCategory,
+}
+
+/// Gives every code point of `s` that [`core_char`](crate::core_char)
+/// drops, in order, with its position in `s` and its category.
+///
+/// This allows to make sure nothing meaningful was thrown away, for
+/// example a `ˉ` standing for a final nu.
+/// # Example :
+/// ```
+/// use gnt_tools::{removed_chars, RemovedCategory};
+///
+/// let removed = removed_chars("16 παραβολὴˉ");
+/// let categories : Vec<_> = removed.iter().map(|r| r.category).collect();
+///
+/// assert_eq!(categories, [RemovedCategory::Digit,
+///                         RemovedCategory::Digit,
+///                         RemovedCategory::Whitespace,
+///                         RemovedCategory::Accent,
+///                         RemovedCategory::Macron]);
+/// assert_eq!(removed[3].ch, '\u{300}');
+/// ```
+pub fn removed_chars(s: &str) -> Vec<RemovedChar> {
+    removed_chars_with(s, &CoreProfile::DEFAULT)
+}
+
+pub(crate) fn removed_chars_with(s: &str, profile: &CoreProfile)
+    -> Vec<RemovedChar> {
+
+    let mut removed = Vec::new();
+    let mut dropped = Vec::new();
+    // The combining marks kept at the text level, composed with their
+    // letter or turned into a letter.
+    let kept : Vec<usize> = match profile.core_text_with(s, Policy::Lenient) {
+        Ok(core) => core.spans()
+                        .iter()
+                        .map(|span| span.char_end - 1)
+                        .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    for (char_index, (offset, c)) in s.char_indices().enumerate() {
+        if is_combining(c) && kept.binary_search(&char_index).is_ok() {
+            continue;
+        }
+        dropped.clear();
+        if profile.fold_char(&c, |i| dropped.push(i)).is_err() {
+            removed.push(RemovedChar {
+                ch: c,
+                offset,
+                char_index,
+                category: RemovedCategory::Unhandled,
+            });
+            continue;
+        }
+        removed.extend(dropped.iter().map(|&i| RemovedChar {
+            ch: i,
+            offset,
+            char_index,
+            category: categorize(i),
+        }));
+    }
+
+    removed
+}
+
+pub(crate) fn categorize(c: char) -> RemovedCategory {
+    use RemovedCategory::*;
+
+    match c {
+        '\u{0300}' | '\u{0301}' | '\u{0302}' | '\u{0303}' | '\u{0342}'
+        | '`' | '´' | '΄' | '῀' => Accent,
+        '\u{0313}' | '\u{0314}' | '\u{0343}'
+        | '᾽' | '᾿' | '῾' | 'ʼ' | 'ʽ' => Breathing,
+        '\u{0308}' | '¨' => Diaeresis,
+        '\u{0345}' | 'ͺ' => IotaSubscript,
+        '\u{0304}' | 'ˉ' | '¯' => Macron,
+        '\u{02B9}' | '\u{0375}' => NumeralSign,
+        '|' | '(' | ')' | '\u{0305}' | '‾' => NominaSacraMarker,
+        _ if c.is_whitespace() => Whitespace,
+        _ if c.is_numeric() => Digit,
+        _ if c.is_ascii_alphabetic()
+            || ('\u{00C0}'..='\u{024F}').contains(&c)
+            || ('\u{1E00}'..='\u{1EFF}').contains(&c) => Latin,
+        _ if c.is_ascii_punctuation()
+            || matches!(c, '·' | ';' | '«' | '»' | '\u{0387}' | '\u{037E}')
+            || ('\u{2000}'..='\u{206F}').contains(&c)
+            || ('\u{2E00}'..='\u{2E7F}').contains(&c) => Punctuation,
+        _ => Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::RemovedCategory::*;
+
+    #[test]
+    fn test_removed_chars_categories() {
+        let s = "|κς| ἀνθρώπῳ ϊ, A¯ 3 \u{0305}";
+        let removed : Vec<_> = removed_chars(s).iter()
+                                               .map(|r| (r.ch, r.category))
+                                               .collect();
+
+        assert_eq!(removed, [
+            ('|', NominaSacraMarker), ('|', NominaSacraMarker),
+            (' ', Whitespace),
+            ('\u{0313}', Breathing), ('\u{0301}', Accent),
+            ('\u{0345}', IotaSubscript),
+            (' ', Whitespace),
+            ('\u{0308}', Diaeresis), (',', Punctuation),
+            (' ', Whitespace),
+            ('A', Latin), ('¯', Macron),
+            (' ', Whitespace),
+            ('3', Digit),
+            (' ', Whitespace),
+            ('\u{0305}', NominaSacraMarker),
+        ]);
+    }
+
+    #[test]
+    fn test_removed_chars_positions() {
+        let s = "λόγος· ϣ";
+        let removed = removed_chars(s);
+
+        assert_eq!(removed[0], RemovedChar {
+            ch: '\u{0301}',
+            offset: 2,
+            char_index: 1,
+            category: Accent,
+        });
+        assert_eq!(removed.last(), Some(&RemovedChar {
+            ch: 'ϣ',
+            offset: s.find('ϣ').unwrap(),
+            char_index: 7,
+            category: Unhandled,
+        }));
+    }
+
+    #[test]
+    fn test_removed_chars_suspended_nu() {
+        let profile = CoreProfile::new().suspended_nu(true);
+        let s = "τη\u{304} ὥρᾳ κ\u{304}ς\u{304} παραβολὴˉ";
+        let macrons : Vec<_> = profile.removed_chars(s)
+                                      .into_iter()
+                                      .filter(|r| r.category == Macron)
+                                      .map(|r| r.char_index)
+                                      .collect();
+
+        // Only the macrons of the nomen sacrum are removed, the others
+        // are read as a nu.
+        assert_eq!(macrons, [9, 11]);
+        assert_eq!(profile.core_text(s).unwrap(), "τηνωρακϲπαραβολην");
+
+        let macrons = removed_chars(s).into_iter()
+                                      .filter(|r| r.category == Macron)
+                                      .count();
+        assert_eq!(macrons, 4);
+    }
+
+    #[test]
+    fn test_removed_chars_complements_core_text() {
+        let s = "16 Εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς λέγων·";
+        let core = crate::core_text(s).unwrap();
+        let kept : usize = core.as_str().chars().count();
+        let decomposed = unicode_normalization::UnicodeNormalization::nfd(s).count();
+
+        assert_eq!(kept + removed_chars(s).len(), decomposed);
+    }
+}
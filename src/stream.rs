@@ -0,0 +1,396 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, ErrorKind};
+use std::iter::Map;
+use std::ops::RangeInclusive;
+
+use unicode_normalization::char::compose;
+
+use crate::profile::MACRON;
+use crate::text::is_word_separator;
+use crate::{is_combining, CoreEngine, CoreProfile, UnhandledGreekChar};
+
+/// Adds [`core_chars`](CoreCharsExt::core_chars) to the iterators of
+/// chars, to normalize them lazily.
+/// # Example :
+/// ```
+/// use gnt_tools::CoreCharsExt;
+///
+/// let core : Result<String, _> = "16 Εἶπεν δὲ".chars().core_chars().collect();
+///
+/// assert_eq!(core.unwrap(), "ειπενδε");
+/// ```
+pub trait CoreCharsExt: Iterator<Item = char> + Sized {
+    /// The core chars of the chars of `self`, same as the letters of
+    /// [`core_text`](crate::core_text).
+    ///
+    /// The offset of an [`UnhandledGreekChar`] is the byte offset of the
+    /// character in the UTF-8 encoding of the chars.
+    fn core_chars(self) -> CoreChars<Self> {
+        self.core_chars_with(&CoreProfile::DEFAULT)
+    }
+
+    /// Same as [`CoreCharsExt::core_chars`], following `profile`.
+    fn core_chars_with(self, profile: &CoreProfile) -> CoreChars<Self> {
+        CoreChars(Stream::new(self.map(Ok as ToOk), profile))
+    }
+}
+
+impl<I: Iterator<Item = char>> CoreCharsExt for I {}
+
+/// Adds [`core_chars`](CoreReadExt::core_chars) to the buffered readers,
+/// to normalize e.g. a huge transcription file without reading it all.
+/// # Example :
+/// ```
+/// use gnt_tools::CoreReadExt;
+/// use std::io::BufReader;
+///
+/// let file = "ΚΑΤΑ ΜΑΘΘΑΙΟΝ  2\n1 Τοῦ δὲ Ἰησοῦ".as_bytes();
+/// let core : std::io::Result<String> = BufReader::new(file).core_chars()
+///                                                          .collect();
+///
+/// assert_eq!(core.unwrap(), "καταμαθθαιοντουδειηϲου");
+/// ```
+pub trait CoreReadExt: BufRead + Sized {
+    /// The core chars of the UTF-8 text read from `self`, same as the
+    /// letters of [`core_text`](crate::core_text).
+    ///
+    /// The characters and their combining marks may be split anywhere
+    /// between two reads. An invalid UTF-8 sequence gives an error of kind
+    /// [`ErrorKind::InvalidData`], and so does an unhandled character,
+    /// with the [`UnhandledGreekChar`] (whose offset is a byte offset in
+    /// the text read) as inner error.
+    fn core_chars(self) -> CoreReadChars<Self> {
+        self.core_chars_with(&CoreProfile::DEFAULT)
+    }
+
+    /// Same as [`CoreReadExt::core_chars`], following `profile`.
+    fn core_chars_with(self, profile: &CoreProfile) -> CoreReadChars<Self> {
+        CoreReadChars(Stream::new(Utf8Chars { reader: self }, profile))
+    }
+}
+
+impl<R: BufRead> CoreReadExt for R {}
+
+impl From<UnhandledGreekChar> for io::Error {
+    fn from(e: UnhandledGreekChar) -> io::Error {
+        io::Error::new(ErrorKind::InvalidData, e)
+    }
+}
+
+/// The iterator returned by [`CoreCharsExt::core_chars`].
+pub struct CoreChars<I>(Stream<Map<I, ToOk>, UnhandledGreekChar>);
+
+type ToOk = fn(char) -> Result<char, Skipped<UnhandledGreekChar>>;
+
+impl<I: Iterator<Item = char>> Iterator for CoreChars<I> {
+    type Item = Result<char, UnhandledGreekChar>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+/// The iterator returned by [`CoreReadExt::core_chars`].
+pub struct CoreReadChars<R>(Stream<Utf8Chars<R>, io::Error>);
+
+impl<R: BufRead> Iterator for CoreReadChars<R> {
+    type Item = io::Result<char>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+/// An error of the source of a [`Stream`], with the number of bytes of the
+/// source it skipped.
+struct Skipped<E> {
+    error: E,
+    len: usize,
+}
+
+/// The core normalization of a stream of chars, following the same rules
+/// as `text::core_text_engine`.
+struct Stream<I, E> {
+    chars: I,
+    /// The chars read ahead to know where a word ends.
+    ahead: VecDeque<Result<char, Skipped<E>>>,
+    engine: CoreEngine,
+    /// The byte offset of the next char.
+    offset: usize,
+    new_word: bool,
+    /// Whether the last letter, and the one before it, have a macron.
+    macron: (bool, bool),
+}
+
+impl<I, E> Stream<I, E>
+    where I: Iterator<Item = Result<char, Skipped<E>>>,
+          E: From<UnhandledGreekChar> {
+
+    fn new(chars: I, profile: &CoreProfile) -> Self {
+        Stream {
+            chars,
+            ahead: VecDeque::new(),
+            engine: profile.engine(),
+            offset: 0,
+            new_word: true,
+            macron: (false, false),
+        }
+    }
+
+    fn pull(&mut self) -> Option<Result<char, Skipped<E>>> {
+        self.ahead.pop_front().or_else(|| self.chars.next())
+    }
+
+    /// Whether the chars to come start by the end of a word, combining
+    /// marks aside. An error is taken as the end of the word.
+    fn ends_word(&mut self) -> bool {
+        let mut i = 0;
+        loop {
+            if i == self.ahead.len() {
+                match self.chars.next() {
+                    Some(c) => self.ahead.push_back(c),
+                    None => return true,
+                }
+            }
+            match self.ahead[i] {
+                Ok(c) if is_combining(c) => i += 1,
+                Ok(c) => return is_word_separator(c),
+                Err(_) => return true,
+            }
+        }
+    }
+
+    /// Composes `letter` with the kept marks that follow it, passing the
+    /// dropped marks on the way. Stops at the macron, which may be a nu.
+    fn compose_marks(&mut self, mut letter: char) -> char {
+        loop {
+            if self.ahead.is_empty() {
+                match self.chars.next() {
+                    Some(c) => self.ahead.push_back(c),
+                    None => return letter,
+                }
+            }
+            let mark = match self.ahead[0] {
+                Ok(c) if is_combining(c) && c != MACRON => c,
+                _ => return letter,
+            };
+            if self.engine.profile().keeps_mark(mark) {
+                match compose(letter, mark) {
+                    Some(composed) => letter = composed,
+                    None => return letter,
+                }
+            } else if self.engine.try_core_char(&mark) != Ok(None) {
+                return letter;
+            }
+            self.ahead.pop_front();
+            self.offset += mark.len_utf8();
+        }
+    }
+
+    fn next(&mut self) -> Option<Result<char, E>> {
+        loop {
+            let c = match self.pull()? {
+                Ok(c) => c,
+                Err(e) => {
+                    self.offset += e.len;
+                    return Some(Err(e.error));
+                }
+            };
+            let offset = self.offset;
+            self.offset += c.len_utf8();
+
+            if is_word_separator(c) {
+                self.new_word = true;
+            }
+            if c == MACRON && self.engine.profile().is_suspended_nu()
+               && !self.new_word && !self.macron.0 {
+                self.macron.0 = true;
+                if !self.macron.1 && self.ends_word() {
+                    return Some(Ok('ν'));
+                }
+            }
+            match self.engine.try_core_char(&c) {
+                Ok(Some(core_c)) => {
+                    self.new_word = false;
+                    self.macron = (false, self.macron.0);
+                    return Some(Ok(self.compose_marks(core_c)));
+                }
+                Ok(None) => (),
+                Err(e) => {
+                    return Some(Err(UnhandledGreekChar { offset, ..e }.into()))
+                }
+            }
+        }
+    }
+}
+
+/// The chars of the UTF-8 text read from `reader`.
+struct Utf8Chars<R> {
+    reader: R,
+}
+
+impl<R: BufRead> Iterator for Utf8Chars<R> {
+    type Item = Result<char, Skipped<io::Error>>;
+
+    /// Gives, with an error, the number of bytes consumed by the char
+    /// that could not be read.
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut bytes = [0; 4];
+        let mut len = 0;
+        let mut width = 1;
+        let invalid = |len| Some(Err(Skipped { error: invalid_utf8(), len }));
+
+        while len < width {
+            let buf = match self.reader.fill_buf() {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Some(Err(Skipped { error, len })),
+            };
+            if buf.is_empty() {
+                // The text ends in the middle of a char.
+                return if len > 0 { invalid(len) } else { None };
+            }
+            if len == 0 {
+                width = utf8_width(buf[0]);
+                if width == 0 {
+                    self.reader.consume(1);
+                    return invalid(1);
+                }
+            } else if !utf8_continuation(bytes[0], len).contains(&buf[0]) {
+                // The byte is left to start the next char.
+                return invalid(len);
+            }
+            bytes[len] = buf[0];
+            self.reader.consume(1);
+            len += 1;
+        }
+
+        match std::str::from_utf8(&bytes[..width]) {
+            Ok(s) => s.chars().next().map(Ok),
+            Err(_) => invalid(width),
+        }
+    }
+}
+
+/// The length of the UTF-8 sequence starting with `b`, `0` if `b` cannot
+/// start one.
+fn utf8_width(b: u8) -> usize {
+    match b {
+        0x00..=0x7F => 1,
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => 0,
+    }
+}
+
+/// The bytes allowed at index `i` of the UTF-8 sequence starting with
+/// `lead`, which excludes overlong forms, surrogates and chars beyond
+/// U+10FFFF.
+fn utf8_continuation(lead: u8, i: usize) -> RangeInclusive<u8> {
+    match (lead, i) {
+        (0xE0, 1) => 0xA0..=0xBF,
+        (0xED, 1) => 0x80..=0x9F,
+        (0xF0, 1) => 0x90..=0xBF,
+        (0xF4, 1) => 0x80..=0x8F,
+        _ => 0x80..=0xBF,
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_text;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_split_across_reads() {
+        let s = "16 Εἶπεν δὲ παραβολὴν πρὸς αὐτούς· ᾄσατε « ἀΐδιος » 𝔊 \
+                 τη\u{304} κ\u{304}ς\u{304} ὥρᾳ\u{304}";
+        let profile = CoreProfile::new().suspended_nu(true);
+        let expected = profile.core_text(s).unwrap();
+
+        for capacity in 1..8 {
+            let reader = BufReader::with_capacity(capacity, s.as_bytes());
+            let core : String = reader.core_chars_with(&profile)
+                                      .collect::<io::Result<_>>()
+                                      .unwrap();
+            assert_eq!(core, expected.as_str(), "capacity {capacity}");
+        }
+
+        let core : Result<String, _> = s.chars().core_chars().collect();
+        assert_eq!(core.unwrap(), core_text(s).unwrap().as_str());
+
+        // The kept marks of a decomposed text.
+        let s = "Ἠσαι\u{308}ου, ω\u{313}\u{345}δῇ, ι\u{301}\u{308} ϣ";
+        let profile = CoreProfile::new().keep_diaeresis(true)
+                                        .keep_iota_subscript(true);
+        for capacity in 1..8 {
+            let reader = BufReader::with_capacity(capacity, s.as_bytes());
+            let core : String = reader.core_chars_with(&profile)
+                                      .map_while(Result::ok)
+                                      .collect();
+            assert_eq!(core, "ηϲαϊουῳδῃϊ", "capacity {capacity}");
+        }
+        let e = s.chars().core_chars_with(&profile).find_map(Result::err);
+        assert_eq!(e.unwrap().offset, s.find('ϣ').unwrap());
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let e = "λόγος ϣ α".chars().core_chars().nth(5).unwrap().unwrap_err();
+        assert_eq!(e.offset, 11);
+
+        let e = BufReader::new("λόγος ϣ".as_bytes()).core_chars()
+                                                     .find_map(Result::err)
+                                                     .unwrap();
+        let inner = e.into_inner().unwrap();
+        assert_eq!(inner.downcast_ref::<UnhandledGreekChar>().unwrap().offset,
+                   11);
+
+        let mut chars = BufReader::new(&b"\xCE\xBB\xCF"[..]).core_chars();
+        assert_eq!(chars.next().unwrap().unwrap(), 'λ');
+        assert_eq!(chars.next().unwrap().unwrap_err().kind(),
+                   ErrorKind::InvalidData);
+        assert!(chars.next().is_none());
+
+        // A truncated char does not swallow the letter after it.
+        for capacity in 1..4 {
+            let reader = BufReader::with_capacity(capacity, &b"\xCEa\xCE\xBB"[..]);
+            let mut chars = reader.core_chars();
+            assert_eq!(chars.next().unwrap().unwrap_err().kind(),
+                       ErrorKind::InvalidData);
+            assert_eq!(chars.next().unwrap().unwrap(), 'λ');
+            assert!(chars.next().is_none());
+        }
+        let chars : Vec<_> = Utf8Chars { reader: &b"\xCEa\xED\xA0\x80b"[..] }
+                                 .map(|c| c.map_err(|e| e.len))
+                                 .collect();
+        assert_eq!(chars, [Err(1), Ok('a'), Err(1), Err(1), Err(1), Ok('b')]);
+
+        // The offsets are the ones of the input, invalid bytes included.
+        let input = b"\xCE\xBB\xFF\xFF \xCE\xBB\xCF\x8C\xCE\xB3\xCF\x8C\xCC\x84 \xCF\xA3";
+        for capacity in 1..8 {
+            let e = BufReader::with_capacity(capacity, &input[..])
+                        .core_chars_with(&CoreProfile::new().suspended_nu(true))
+                        .filter_map(Result::err)
+                        .last()
+                        .unwrap();
+            let inner = e.into_inner().unwrap();
+            let e = inner.downcast_ref::<UnhandledGreekChar>().unwrap();
+            assert_eq!(e.offset, 16, "capacity {capacity}");
+            assert_eq!(&input[e.offset..], "ϣ".as_bytes());
+        }
+        let e = BufReader::new(&b"\xCE\xCEa\xCF\xA3"[..]).core_chars()
+                                                           .filter_map(Result::err)
+                                                           .last()
+                                                           .unwrap();
+        let inner = e.into_inner().unwrap();
+        assert_eq!(inner.downcast_ref::<UnhandledGreekChar>().unwrap().offset,
+                   3);
+    }
+}
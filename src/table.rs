@@ -0,0 +1,164 @@
+//! The core mapping of every code point of the Greek and Coptic
+//! (U+0370–U+03FF), Greek Extended (U+1F00–U+1FFF) and Combining
+//! Diacritical Marks (U+0300–U+036F) blocks.
+//!
+//! The table is generated from the canonical decomposition of each code
+//! point (see `CoreProfile::fold_char`) and checked in, so that every
+//! mapping can be audited. Run `cargo test -- --ignored generate_table`
+//! to generate it again.
+//!
+//! The code points not assigned by Unicode are not handled.
+
+mod generated;
+
+use generated::{COMBINING, GREEK_AND_COPTIC, GREEK_EXTENDED};
+
+/// The marks of a letter that some profiles keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Marks(u8);
+
+impl Marks {
+    pub(crate) const NONE: Marks = Marks(0);
+    pub(crate) const DIAERESIS: Marks = Marks(1);
+    pub(crate) const IOTA_SUBSCRIPT: Marks = Marks(2);
+
+    pub(crate) fn contains(self, other: Marks) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// The core mapping of a code point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Mapping {
+    /// A letter, in the case of the code point and before any sigma rule,
+    /// with its marks.
+    Letter(char, Marks),
+    /// The code point is deleted.
+    Drop,
+    /// The code point is not handled.
+    Unhandled,
+}
+
+/// The mapping of `c`, if it is in one of the blocks of the table.
+#[inline]
+pub(crate) fn lookup(c: char) -> Option<Mapping> {
+    let c = c as usize;
+    match c {
+        0x0300..=0x036F => Some(COMBINING[c - 0x0300]),
+        0x0370..=0x03FF => Some(GREEK_AND_COPTIC[c - 0x0370]),
+        0x1F00..=0x1FFF => Some(GREEK_EXTENDED[c - 0x1F00]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CoreProfile;
+    use std::fmt::Write;
+
+    const BLOCKS: [(&str, u32, u32); 3] = [
+        ("COMBINING", 0x0300, 0x036F),
+        ("GREEK_AND_COPTIC", 0x0370, 0x03FF),
+        ("GREEK_EXTENDED", 0x1F00, 0x1FFF),
+    ];
+
+    /// The mapping of `c` computed from its canonical decomposition.
+    fn mapping(c: char) -> Mapping {
+        let mut marks = Marks::NONE;
+        let identity = CoreProfile::new().keep_case(true)
+                                         .medial_sigma(true)
+                                         .keep_final_sigma(true);
+        let core = identity.fold_char(&c, |i| match i {
+            '\u{0308}' => marks.0 |= Marks::DIAERESIS.0,
+            '\u{0345}' => marks.0 |= Marks::IOTA_SUBSCRIPT.0,
+            _ => (),
+        });
+
+        match core {
+            Ok(Some(l)) => Mapping::Letter(l, marks),
+            Ok(None) => Mapping::Drop,
+            Err(_) => Mapping::Unhandled,
+        }
+    }
+
+    fn render() -> String {
+        let mut out = String::from(
+            "// This file is generated by `cargo test -- --ignored \
+             generate_table`.\n// Do not edit it by hand.\n\n\
+             use super::Mapping::{self, Drop, Letter, Unhandled};\n\
+             use super::Marks;\n\n\
+             const N: Marks = Marks::NONE;\n\
+             const D: Marks = Marks::DIAERESIS;\n\
+             const I: Marks = Marks::IOTA_SUBSCRIPT;\n");
+
+        for (name, first, last) in BLOCKS {
+            writeln!(out, "\npub(super) static {name}: [Mapping; {}] = [",
+                     last - first + 1).unwrap();
+            for c in (first..=last).filter_map(char::from_u32) {
+                let shown = if crate::is_combining(c) {
+                    format!("◌{c}")
+                } else {
+                    c.to_string()
+                };
+                let mapping = match mapping(c) {
+                    Mapping::Letter(l, m) => {
+                        let m = match m {
+                            Marks::DIAERESIS => "D",
+                            Marks::IOTA_SUBSCRIPT => "I",
+                            _ => "N",
+                        };
+                        format!("Letter('{l}', {m})")
+                    }
+                    Mapping::Drop => String::from("Drop"),
+                    Mapping::Unhandled => String::from("Unhandled"),
+                };
+                writeln!(out, "    /* U+{:04X} {shown} */ {mapping},", c as u32)
+                    .unwrap();
+            }
+            out.push_str("];\n");
+        }
+
+        out
+    }
+
+    #[test]
+    #[ignore]
+    fn generate_table() {
+        std::fs::write(concat!(env!("CARGO_MANIFEST_DIR"),
+                               "/src/table/generated.rs"),
+                       render()).unwrap();
+    }
+
+    #[test]
+    fn test_table_is_up_to_date() {
+        assert!(render() == include_str!("table/generated.rs"),
+                "src/table/generated.rs is outdated, run \
+                 `cargo test -- --ignored generate_table`");
+    }
+
+    // The table is generated from the fold, so this only checks that the
+    // lookup and the fold agree for every profile. The expected mappings
+    // themselves are written by hand in the tests of the crate root.
+    #[test]
+    fn test_table_matches_fold() {
+        let profiles = [
+            CoreProfile::new(),
+            CoreProfile::new().keep_case(true).medial_sigma(true),
+            CoreProfile::new().keep_final_sigma(true).keep_diaeresis(true),
+            CoreProfile::new().keep_iota_subscript(true).keep_case(true),
+            CoreProfile::new().keep_diaeresis(true)
+                              .keep_iota_subscript(true)
+                              .suspended_nu(true),
+        ];
+
+        for (_, first, last) in BLOCKS {
+            for c in (first..=last).filter_map(char::from_u32) {
+                for p in &profiles {
+                    assert_eq!(p.try_core_char(&c), p.fold_char(&c, |_| ()),
+                               "U+{:04X} with {p:?}", c as u32);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,530 @@
+// This file is generated by `cargo test -- --ignored generate_table`.
+// Do not edit it by hand.
+
+use super::Mapping::{self, Drop, Letter, Unhandled};
+use super::Marks;
+
+const N: Marks = Marks::NONE;
+const D: Marks = Marks::DIAERESIS;
+const I: Marks = Marks::IOTA_SUBSCRIPT;
+
+pub(super) static COMBINING: [Mapping; 112] = [
+    /* U+0300 ◌̀ */ Drop,
+    /* U+0301 ◌́ */ Drop,
+    /* U+0302 ◌̂ */ Drop,
+    /* U+0303 ◌̃ */ Drop,
+    /* U+0304 ◌̄ */ Drop,
+    /* U+0305 ◌̅ */ Drop,
+    /* U+0306 ◌̆ */ Drop,
+    /* U+0307 ◌̇ */ Drop,
+    /* U+0308 ◌̈ */ Drop,
+    /* U+0309 ◌̉ */ Drop,
+    /* U+030A ◌̊ */ Drop,
+    /* U+030B ◌̋ */ Drop,
+    /* U+030C ◌̌ */ Drop,
+    /* U+030D ◌̍ */ Drop,
+    /* U+030E ◌̎ */ Drop,
+    /* U+030F ◌̏ */ Drop,
+    /* U+0310 ◌̐ */ Drop,
+    /* U+0311 ◌̑ */ Drop,
+    /* U+0312 ◌̒ */ Drop,
+    /* U+0313 ◌̓ */ Drop,
+    /* U+0314 ◌̔ */ Drop,
+    /* U+0315 ◌̕ */ Drop,
+    /* U+0316 ◌̖ */ Drop,
+    /* U+0317 ◌̗ */ Drop,
+    /* U+0318 ◌̘ */ Drop,
+    /* U+0319 ◌̙ */ Drop,
+    /* U+031A ◌̚ */ Drop,
+    /* U+031B ◌̛ */ Drop,
+    /* U+031C ◌̜ */ Drop,
+    /* U+031D ◌̝ */ Drop,
+    /* U+031E ◌̞ */ Drop,
+    /* U+031F ◌̟ */ Drop,
+    /* U+0320 ◌̠ */ Drop,
+    /* U+0321 ◌̡ */ Drop,
+    /* U+0322 ◌̢ */ Drop,
+    /* U+0323 ◌̣ */ Drop,
+    /* U+0324 ◌̤ */ Drop,
+    /* U+0325 ◌̥ */ Drop,
+    /* U+0326 ◌̦ */ Drop,
+    /* U+0327 ◌̧ */ Drop,
+    /* U+0328 ◌̨ */ Drop,
+    /* U+0329 ◌̩ */ Drop,
+    /* U+032A ◌̪ */ Drop,
+    /* U+032B ◌̫ */ Drop,
+    /* U+032C ◌̬ */ Drop,
+    /* U+032D ◌̭ */ Drop,
+    /* U+032E ◌̮ */ Drop,
+    /* U+032F ◌̯ */ Drop,
+    /* U+0330 ◌̰ */ Drop,
+    /* U+0331 ◌̱ */ Drop,
+    /* U+0332 ◌̲ */ Drop,
+    /* U+0333 ◌̳ */ Drop,
+    /* U+0334 ◌̴ */ Drop,
+    /* U+0335 ◌̵ */ Drop,
+    /* U+0336 ◌̶ */ Drop,
+    /* U+0337 ◌̷ */ Drop,
+    /* U+0338 ◌̸ */ Drop,
+    /* U+0339 ◌̹ */ Drop,
+    /* U+033A ◌̺ */ Drop,
+    /* U+033B ◌̻ */ Drop,
+    /* U+033C ◌̼ */ Drop,
+    /* U+033D ◌̽ */ Drop,
+    /* U+033E ◌̾ */ Drop,
+    /* U+033F ◌̿ */ Drop,
+    /* U+0340 ◌̀ */ Drop,
+    /* U+0341 ◌́ */ Drop,
+    /* U+0342 ◌͂ */ Drop,
+    /* U+0343 ◌̓ */ Drop,
+    /* U+0344 ◌̈́ */ Drop,
+    /* U+0345 ◌ͅ */ Drop,
+    /* U+0346 ◌͆ */ Drop,
+    /* U+0347 ◌͇ */ Drop,
+    /* U+0348 ◌͈ */ Drop,
+    /* U+0349 ◌͉ */ Drop,
+    /* U+034A ◌͊ */ Drop,
+    /* U+034B ◌͋ */ Drop,
+    /* U+034C ◌͌ */ Drop,
+    /* U+034D ◌͍ */ Drop,
+    /* U+034E ◌͎ */ Drop,
+    /* U+034F ◌͏ */ Drop,
+    /* U+0350 ◌͐ */ Drop,
+    /* U+0351 ◌͑ */ Drop,
+    /* U+0352 ◌͒ */ Drop,
+    /* U+0353 ◌͓ */ Drop,
+    /* U+0354 ◌͔ */ Drop,
+    /* U+0355 ◌͕ */ Drop,
+    /* U+0356 ◌͖ */ Drop,
+    /* U+0357 ◌͗ */ Drop,
+    /* U+0358 ◌͘ */ Drop,
+    /* U+0359 ◌͙ */ Drop,
+    /* U+035A ◌͚ */ Drop,
+    /* U+035B ◌͛ */ Drop,
+    /* U+035C ◌͜ */ Drop,
+    /* U+035D ◌͝ */ Drop,
+    /* U+035E ◌͞ */ Drop,
+    /* U+035F ◌͟ */ Drop,
+    /* U+0360 ◌͠ */ Drop,
+    /* U+0361 ◌͡ */ Drop,
+    /* U+0362 ◌͢ */ Drop,
+    /* U+0363 ◌ͣ */ Drop,
+    /* U+0364 ◌ͤ */ Drop,
+    /* U+0365 ◌ͥ */ Drop,
+    /* U+0366 ◌ͦ */ Drop,
+    /* U+0367 ◌ͧ */ Drop,
+    /* U+0368 ◌ͨ */ Drop,
+    /* U+0369 ◌ͩ */ Drop,
+    /* U+036A ◌ͪ */ Drop,
+    /* U+036B ◌ͫ */ Drop,
+    /* U+036C ◌ͬ */ Drop,
+    /* U+036D ◌ͭ */ Drop,
+    /* U+036E ◌ͮ */ Drop,
+    /* U+036F ◌ͯ */ Drop,
+];
+
+pub(super) static GREEK_AND_COPTIC: [Mapping; 144] = [
+    /* U+0370 Ͱ */ Letter('Ͱ', N),
+    /* U+0371 ͱ */ Letter('ͱ', N),
+    /* U+0372 Ͳ */ Letter('Ϡ', N),
+    /* U+0373 ͳ */ Letter('ϡ', N),
+    /* U+0374 ʹ */ Drop,
+    /* U+0375 ͵ */ Drop,
+    /* U+0376 Ͷ */ Letter('Ϛ', N),
+    /* U+0377 ͷ */ Letter('ϛ', N),
+    /* U+0378 ͸ */ Unhandled,
+    /* U+0379 ͹ */ Unhandled,
+    /* U+037A ͺ */ Drop,
+    /* U+037B ͻ */ Letter('σ', N),
+    /* U+037C ͼ */ Letter('σ', N),
+    /* U+037D ͽ */ Letter('σ', N),
+    /* U+037E ; */ Drop,
+    /* U+037F Ϳ */ Letter('Ϳ', N),
+    /* U+0380 ΀ */ Unhandled,
+    /* U+0381 ΁ */ Unhandled,
+    /* U+0382 ΂ */ Unhandled,
+    /* U+0383 ΃ */ Unhandled,
+    /* U+0384 ΄ */ Drop,
+    /* U+0385 ΅ */ Drop,
+    /* U+0386 Ά */ Letter('Α', N),
+    /* U+0387 · */ Drop,
+    /* U+0388 Έ */ Letter('Ε', N),
+    /* U+0389 Ή */ Letter('Η', N),
+    /* U+038A Ί */ Letter('Ι', N),
+    /* U+038B ΋ */ Unhandled,
+    /* U+038C Ό */ Letter('Ο', N),
+    /* U+038D ΍ */ Unhandled,
+    /* U+038E Ύ */ Letter('Υ', N),
+    /* U+038F Ώ */ Letter('Ω', N),
+    /* U+0390 ΐ */ Letter('ι', D),
+    /* U+0391 Α */ Letter('Α', N),
+    /* U+0392 Β */ Letter('Β', N),
+    /* U+0393 Γ */ Letter('Γ', N),
+    /* U+0394 Δ */ Letter('Δ', N),
+    /* U+0395 Ε */ Letter('Ε', N),
+    /* U+0396 Ζ */ Letter('Ζ', N),
+    /* U+0397 Η */ Letter('Η', N),
+    /* U+0398 Θ */ Letter('Θ', N),
+    /* U+0399 Ι */ Letter('Ι', N),
+    /* U+039A Κ */ Letter('Κ', N),
+    /* U+039B Λ */ Letter('Λ', N),
+    /* U+039C Μ */ Letter('Μ', N),
+    /* U+039D Ν */ Letter('Ν', N),
+    /* U+039E Ξ */ Letter('Ξ', N),
+    /* U+039F Ο */ Letter('Ο', N),
+    /* U+03A0 Π */ Letter('Π', N),
+    /* U+03A1 Ρ */ Letter('Ρ', N),
+    /* U+03A2 ΢ */ Unhandled,
+    /* U+03A3 Σ */ Letter('Σ', N),
+    /* U+03A4 Τ */ Letter('Τ', N),
+    /* U+03A5 Υ */ Letter('Υ', N),
+    /* U+03A6 Φ */ Letter('Φ', N),
+    /* U+03A7 Χ */ Letter('Χ', N),
+    /* U+03A8 Ψ */ Letter('Ψ', N),
+    /* U+03A9 Ω */ Letter('Ω', N),
+    /* U+03AA Ϊ */ Letter('Ι', D),
+    /* U+03AB Ϋ */ Letter('Υ', D),
+    /* U+03AC ά */ Letter('α', N),
+    /* U+03AD έ */ Letter('ε', N),
+    /* U+03AE ή */ Letter('η', N),
+    /* U+03AF ί */ Letter('ι', N),
+    /* U+03B0 ΰ */ Letter('υ', D),
+    /* U+03B1 α */ Letter('α', N),
+    /* U+03B2 β */ Letter('β', N),
+    /* U+03B3 γ */ Letter('γ', N),
+    /* U+03B4 δ */ Letter('δ', N),
+    /* U+03B5 ε */ Letter('ε', N),
+    /* U+03B6 ζ */ Letter('ζ', N),
+    /* U+03B7 η */ Letter('η', N),
+    /* U+03B8 θ */ Letter('θ', N),
+    /* U+03B9 ι */ Letter('ι', N),
+    /* U+03BA κ */ Letter('κ', N),
+    /* U+03BB λ */ Letter('λ', N),
+    /* U+03BC μ */ Letter('μ', N),
+    /* U+03BD ν */ Letter('ν', N),
+    /* U+03BE ξ */ Letter('ξ', N),
+    /* U+03BF ο */ Letter('ο', N),
+    /* U+03C0 π */ Letter('π', N),
+    /* U+03C1 ρ */ Letter('ρ', N),
+    /* U+03C2 ς */ Letter('ς', N),
+    /* U+03C3 σ */ Letter('σ', N),
+    /* U+03C4 τ */ Letter('τ', N),
+    /* U+03C5 υ */ Letter('υ', N),
+    /* U+03C6 φ */ Letter('φ', N),
+    /* U+03C7 χ */ Letter('χ', N),
+    /* U+03C8 ψ */ Letter('ψ', N),
+    /* U+03C9 ω */ Letter('ω', N),
+    /* U+03CA ϊ */ Letter('ι', D),
+    /* U+03CB ϋ */ Letter('υ', D),
+    /* U+03CC ό */ Letter('ο', N),
+    /* U+03CD ύ */ Letter('υ', N),
+    /* U+03CE ώ */ Letter('ω', N),
+    /* U+03CF Ϗ */ Letter('Ϗ', N),
+    /* U+03D0 ϐ */ Letter('β', N),
+    /* U+03D1 ϑ */ Letter('θ', N),
+    /* U+03D2 ϒ */ Letter('Υ', N),
+    /* U+03D3 ϓ */ Letter('Υ', N),
+    /* U+03D4 ϔ */ Letter('Υ', D),
+    /* U+03D5 ϕ */ Letter('φ', N),
+    /* U+03D6 ϖ */ Letter('π', N),
+    /* U+03D7 ϗ */ Letter('ϗ', N),
+    /* U+03D8 Ϙ */ Letter('Ϟ', N),
+    /* U+03D9 ϙ */ Letter('ϟ', N),
+    /* U+03DA Ϛ */ Letter('Ϛ', N),
+    /* U+03DB ϛ */ Letter('ϛ', N),
+    /* U+03DC Ϝ */ Letter('Ϛ', N),
+    /* U+03DD ϝ */ Letter('ϛ', N),
+    /* U+03DE Ϟ */ Letter('Ϟ', N),
+    /* U+03DF ϟ */ Letter('ϟ', N),
+    /* U+03E0 Ϡ */ Letter('Ϡ', N),
+    /* U+03E1 ϡ */ Letter('ϡ', N),
+    /* U+03E2 Ϣ */ Unhandled,
+    /* U+03E3 ϣ */ Unhandled,
+    /* U+03E4 Ϥ */ Unhandled,
+    /* U+03E5 ϥ */ Unhandled,
+    /* U+03E6 Ϧ */ Unhandled,
+    /* U+03E7 ϧ */ Unhandled,
+    /* U+03E8 Ϩ */ Unhandled,
+    /* U+03E9 ϩ */ Unhandled,
+    /* U+03EA Ϫ */ Unhandled,
+    /* U+03EB ϫ */ Unhandled,
+    /* U+03EC Ϭ */ Unhandled,
+    /* U+03ED ϭ */ Unhandled,
+    /* U+03EE Ϯ */ Unhandled,
+    /* U+03EF ϯ */ Unhandled,
+    /* U+03F0 ϰ */ Letter('κ', N),
+    /* U+03F1 ϱ */ Letter('ρ', N),
+    /* U+03F2 ϲ */ Letter('σ', N),
+    /* U+03F3 ϳ */ Letter('ϳ', N),
+    /* U+03F4 ϴ */ Letter('Θ', N),
+    /* U+03F5 ϵ */ Letter('ε', N),
+    /* U+03F6 ϶ */ Drop,
+    /* U+03F7 Ϸ */ Unhandled,
+    /* U+03F8 ϸ */ Unhandled,
+    /* U+03F9 Ϲ */ Letter('Σ', N),
+    /* U+03FA Ϻ */ Letter('Σ', N),
+    /* U+03FB ϻ */ Letter('σ', N),
+    /* U+03FC ϼ */ Letter('ρ', N),
+    /* U+03FD Ͻ */ Letter('Σ', N),
+    /* U+03FE Ͼ */ Letter('Σ', N),
+    /* U+03FF Ͽ */ Letter('Σ', N),
+];
+
+pub(super) static GREEK_EXTENDED: [Mapping; 256] = [
+    /* U+1F00 ἀ */ Letter('α', N),
+    /* U+1F01 ἁ */ Letter('α', N),
+    /* U+1F02 ἂ */ Letter('α', N),
+    /* U+1F03 ἃ */ Letter('α', N),
+    /* U+1F04 ἄ */ Letter('α', N),
+    /* U+1F05 ἅ */ Letter('α', N),
+    /* U+1F06 ἆ */ Letter('α', N),
+    /* U+1F07 ἇ */ Letter('α', N),
+    /* U+1F08 Ἀ */ Letter('Α', N),
+    /* U+1F09 Ἁ */ Letter('Α', N),
+    /* U+1F0A Ἂ */ Letter('Α', N),
+    /* U+1F0B Ἃ */ Letter('Α', N),
+    /* U+1F0C Ἄ */ Letter('Α', N),
+    /* U+1F0D Ἅ */ Letter('Α', N),
+    /* U+1F0E Ἆ */ Letter('Α', N),
+    /* U+1F0F Ἇ */ Letter('Α', N),
+    /* U+1F10 ἐ */ Letter('ε', N),
+    /* U+1F11 ἑ */ Letter('ε', N),
+    /* U+1F12 ἒ */ Letter('ε', N),
+    /* U+1F13 ἓ */ Letter('ε', N),
+    /* U+1F14 ἔ */ Letter('ε', N),
+    /* U+1F15 ἕ */ Letter('ε', N),
+    /* U+1F16 ἖ */ Unhandled,
+    /* U+1F17 ἗ */ Unhandled,
+    /* U+1F18 Ἐ */ Letter('Ε', N),
+    /* U+1F19 Ἑ */ Letter('Ε', N),
+    /* U+1F1A Ἒ */ Letter('Ε', N),
+    /* U+1F1B Ἓ */ Letter('Ε', N),
+    /* U+1F1C Ἔ */ Letter('Ε', N),
+    /* U+1F1D Ἕ */ Letter('Ε', N),
+    /* U+1F1E ἞ */ Unhandled,
+    /* U+1F1F ἟ */ Unhandled,
+    /* U+1F20 ἠ */ Letter('η', N),
+    /* U+1F21 ἡ */ Letter('η', N),
+    /* U+1F22 ἢ */ Letter('η', N),
+    /* U+1F23 ἣ */ Letter('η', N),
+    /* U+1F24 ἤ */ Letter('η', N),
+    /* U+1F25 ἥ */ Letter('η', N),
+    /* U+1F26 ἦ */ Letter('η', N),
+    /* U+1F27 ἧ */ Letter('η', N),
+    /* U+1F28 Ἠ */ Letter('Η', N),
+    /* U+1F29 Ἡ */ Letter('Η', N),
+    /* U+1F2A Ἢ */ Letter('Η', N),
+    /* U+1F2B Ἣ */ Letter('Η', N),
+    /* U+1F2C Ἤ */ Letter('Η', N),
+    /* U+1F2D Ἥ */ Letter('Η', N),
+    /* U+1F2E Ἦ */ Letter('Η', N),
+    /* U+1F2F Ἧ */ Letter('Η', N),
+    /* U+1F30 ἰ */ Letter('ι', N),
+    /* U+1F31 ἱ */ Letter('ι', N),
+    /* U+1F32 ἲ */ Letter('ι', N),
+    /* U+1F33 ἳ */ Letter('ι', N),
+    /* U+1F34 ἴ */ Letter('ι', N),
+    /* U+1F35 ἵ */ Letter('ι', N),
+    /* U+1F36 ἶ */ Letter('ι', N),
+    /* U+1F37 ἷ */ Letter('ι', N),
+    /* U+1F38 Ἰ */ Letter('Ι', N),
+    /* U+1F39 Ἱ */ Letter('Ι', N),
+    /* U+1F3A Ἲ */ Letter('Ι', N),
+    /* U+1F3B Ἳ */ Letter('Ι', N),
+    /* U+1F3C Ἴ */ Letter('Ι', N),
+    /* U+1F3D Ἵ */ Letter('Ι', N),
+    /* U+1F3E Ἶ */ Letter('Ι', N),
+    /* U+1F3F Ἷ */ Letter('Ι', N),
+    /* U+1F40 ὀ */ Letter('ο', N),
+    /* U+1F41 ὁ */ Letter('ο', N),
+    /* U+1F42 ὂ */ Letter('ο', N),
+    /* U+1F43 ὃ */ Letter('ο', N),
+    /* U+1F44 ὄ */ Letter('ο', N),
+    /* U+1F45 ὅ */ Letter('ο', N),
+    /* U+1F46 ὆ */ Unhandled,
+    /* U+1F47 ὇ */ Unhandled,
+    /* U+1F48 Ὀ */ Letter('Ο', N),
+    /* U+1F49 Ὁ */ Letter('Ο', N),
+    /* U+1F4A Ὂ */ Letter('Ο', N),
+    /* U+1F4B Ὃ */ Letter('Ο', N),
+    /* U+1F4C Ὄ */ Letter('Ο', N),
+    /* U+1F4D Ὅ */ Letter('Ο', N),
+    /* U+1F4E ὎ */ Unhandled,
+    /* U+1F4F ὏ */ Unhandled,
+    /* U+1F50 ὐ */ Letter('υ', N),
+    /* U+1F51 ὑ */ Letter('υ', N),
+    /* U+1F52 ὒ */ Letter('υ', N),
+    /* U+1F53 ὓ */ Letter('υ', N),
+    /* U+1F54 ὔ */ Letter('υ', N),
+    /* U+1F55 ὕ */ Letter('υ', N),
+    /* U+1F56 ὖ */ Letter('υ', N),
+    /* U+1F57 ὗ */ Letter('υ', N),
+    /* U+1F58 ὘ */ Unhandled,
+    /* U+1F59 Ὑ */ Letter('Υ', N),
+    /* U+1F5A ὚ */ Unhandled,
+    /* U+1F5B Ὓ */ Letter('Υ', N),
+    /* U+1F5C ὜ */ Unhandled,
+    /* U+1F5D Ὕ */ Letter('Υ', N),
+    /* U+1F5E ὞ */ Unhandled,
+    /* U+1F5F Ὗ */ Letter('Υ', N),
+    /* U+1F60 ὠ */ Letter('ω', N),
+    /* U+1F61 ὡ */ Letter('ω', N),
+    /* U+1F62 ὢ */ Letter('ω', N),
+    /* U+1F63 ὣ */ Letter('ω', N),
+    /* U+1F64 ὤ */ Letter('ω', N),
+    /* U+1F65 ὥ */ Letter('ω', N),
+    /* U+1F66 ὦ */ Letter('ω', N),
+    /* U+1F67 ὧ */ Letter('ω', N),
+    /* U+1F68 Ὠ */ Letter('Ω', N),
+    /* U+1F69 Ὡ */ Letter('Ω', N),
+    /* U+1F6A Ὢ */ Letter('Ω', N),
+    /* U+1F6B Ὣ */ Letter('Ω', N),
+    /* U+1F6C Ὤ */ Letter('Ω', N),
+    /* U+1F6D Ὥ */ Letter('Ω', N),
+    /* U+1F6E Ὦ */ Letter('Ω', N),
+    /* U+1F6F Ὧ */ Letter('Ω', N),
+    /* U+1F70 ὰ */ Letter('α', N),
+    /* U+1F71 ά */ Letter('α', N),
+    /* U+1F72 ὲ */ Letter('ε', N),
+    /* U+1F73 έ */ Letter('ε', N),
+    /* U+1F74 ὴ */ Letter('η', N),
+    /* U+1F75 ή */ Letter('η', N),
+    /* U+1F76 ὶ */ Letter('ι', N),
+    /* U+1F77 ί */ Letter('ι', N),
+    /* U+1F78 ὸ */ Letter('ο', N),
+    /* U+1F79 ό */ Letter('ο', N),
+    /* U+1F7A ὺ */ Letter('υ', N),
+    /* U+1F7B ύ */ Letter('υ', N),
+    /* U+1F7C ὼ */ Letter('ω', N),
+    /* U+1F7D ώ */ Letter('ω', N),
+    /* U+1F7E ὾ */ Unhandled,
+    /* U+1F7F ὿ */ Unhandled,
+    /* U+1F80 ᾀ */ Letter('α', I),
+    /* U+1F81 ᾁ */ Letter('α', I),
+    /* U+1F82 ᾂ */ Letter('α', I),
+    /* U+1F83 ᾃ */ Letter('α', I),
+    /* U+1F84 ᾄ */ Letter('α', I),
+    /* U+1F85 ᾅ */ Letter('α', I),
+    /* U+1F86 ᾆ */ Letter('α', I),
+    /* U+1F87 ᾇ */ Letter('α', I),
+    /* U+1F88 ᾈ */ Letter('Α', I),
+    /* U+1F89 ᾉ */ Letter('Α', I),
+    /* U+1F8A ᾊ */ Letter('Α', I),
+    /* U+1F8B ᾋ */ Letter('Α', I),
+    /* U+1F8C ᾌ */ Letter('Α', I),
+    /* U+1F8D ᾍ */ Letter('Α', I),
+    /* U+1F8E ᾎ */ Letter('Α', I),
+    /* U+1F8F ᾏ */ Letter('Α', I),
+    /* U+1F90 ᾐ */ Letter('η', I),
+    /* U+1F91 ᾑ */ Letter('η', I),
+    /* U+1F92 ᾒ */ Letter('η', I),
+    /* U+1F93 ᾓ */ Letter('η', I),
+    /* U+1F94 ᾔ */ Letter('η', I),
+    /* U+1F95 ᾕ */ Letter('η', I),
+    /* U+1F96 ᾖ */ Letter('η', I),
+    /* U+1F97 ᾗ */ Letter('η', I),
+    /* U+1F98 ᾘ */ Letter('Η', I),
+    /* U+1F99 ᾙ */ Letter('Η', I),
+    /* U+1F9A ᾚ */ Letter('Η', I),
+    /* U+1F9B ᾛ */ Letter('Η', I),
+    /* U+1F9C ᾜ */ Letter('Η', I),
+    /* U+1F9D ᾝ */ Letter('Η', I),
+    /* U+1F9E ᾞ */ Letter('Η', I),
+    /* U+1F9F ᾟ */ Letter('Η', I),
+    /* U+1FA0 ᾠ */ Letter('ω', I),
+    /* U+1FA1 ᾡ */ Letter('ω', I),
+    /* U+1FA2 ᾢ */ Letter('ω', I),
+    /* U+1FA3 ᾣ */ Letter('ω', I),
+    /* U+1FA4 ᾤ */ Letter('ω', I),
+    /* U+1FA5 ᾥ */ Letter('ω', I),
+    /* U+1FA6 ᾦ */ Letter('ω', I),
+    /* U+1FA7 ᾧ */ Letter('ω', I),
+    /* U+1FA8 ᾨ */ Letter('Ω', I),
+    /* U+1FA9 ᾩ */ Letter('Ω', I),
+    /* U+1FAA ᾪ */ Letter('Ω', I),
+    /* U+1FAB ᾫ */ Letter('Ω', I),
+    /* U+1FAC ᾬ */ Letter('Ω', I),
+    /* U+1FAD ᾭ */ Letter('Ω', I),
+    /* U+1FAE ᾮ */ Letter('Ω', I),
+    /* U+1FAF ᾯ */ Letter('Ω', I),
+    /* U+1FB0 ᾰ */ Letter('α', N),
+    /* U+1FB1 ᾱ */ Letter('α', N),
+    /* U+1FB2 ᾲ */ Letter('α', I),
+    /* U+1FB3 ᾳ */ Letter('α', I),
+    /* U+1FB4 ᾴ */ Letter('α', I),
+    /* U+1FB5 ᾵ */ Unhandled,
+    /* U+1FB6 ᾶ */ Letter('α', N),
+    /* U+1FB7 ᾷ */ Letter('α', I),
+    /* U+1FB8 Ᾰ */ Letter('Α', N),
+    /* U+1FB9 Ᾱ */ Letter('Α', N),
+    /* U+1FBA Ὰ */ Letter('Α', N),
+    /* U+1FBB Ά */ Letter('Α', N),
+    /* U+1FBC ᾼ */ Letter('Α', I),
+    /* U+1FBD ᾽ */ Drop,
+    /* U+1FBE ι */ Letter('ι', N),
+    /* U+1FBF ᾿ */ Drop,
+    /* U+1FC0 ῀ */ Drop,
+    /* U+1FC1 ῁ */ Drop,
+    /* U+1FC2 ῂ */ Letter('η', I),
+    /* U+1FC3 ῃ */ Letter('η', I),
+    /* U+1FC4 ῄ */ Letter('η', I),
+    /* U+1FC5 ῅ */ Unhandled,
+    /* U+1FC6 ῆ */ Letter('η', N),
+    /* U+1FC7 ῇ */ Letter('η', I),
+    /* U+1FC8 Ὲ */ Letter('Ε', N),
+    /* U+1FC9 Έ */ Letter('Ε', N),
+    /* U+1FCA Ὴ */ Letter('Η', N),
+    /* U+1FCB Ή */ Letter('Η', N),
+    /* U+1FCC ῌ */ Letter('Η', I),
+    /* U+1FCD ῍ */ Drop,
+    /* U+1FCE ῎ */ Drop,
+    /* U+1FCF ῏ */ Drop,
+    /* U+1FD0 ῐ */ Letter('ι', N),
+    /* U+1FD1 ῑ */ Letter('ι', N),
+    /* U+1FD2 ῒ */ Letter('ι', D),
+    /* U+1FD3 ΐ */ Letter('ι', D),
+    /* U+1FD4 ῔ */ Unhandled,
+    /* U+1FD5 ῕ */ Unhandled,
+    /* U+1FD6 ῖ */ Letter('ι', N),
+    /* U+1FD7 ῗ */ Letter('ι', D),
+    /* U+1FD8 Ῐ */ Letter('Ι', N),
+    /* U+1FD9 Ῑ */ Letter('Ι', N),
+    /* U+1FDA Ὶ */ Letter('Ι', N),
+    /* U+1FDB Ί */ Letter('Ι', N),
+    /* U+1FDC ῜ */ Unhandled,
+    /* U+1FDD ῝ */ Drop,
+    /* U+1FDE ῞ */ Drop,
+    /* U+1FDF ῟ */ Drop,
+    /* U+1FE0 ῠ */ Letter('υ', N),
+    /* U+1FE1 ῡ */ Letter('υ', N),
+    /* U+1FE2 ῢ */ Letter('υ', D),
+    /* U+1FE3 ΰ */ Letter('υ', D),
+    /* U+1FE4 ῤ */ Letter('ρ', N),
+    /* U+1FE5 ῥ */ Letter('ρ', N),
+    /* U+1FE6 ῦ */ Letter('υ', N),
+    /* U+1FE7 ῧ */ Letter('υ', D),
+    /* U+1FE8 Ῠ */ Letter('Υ', N),
+    /* U+1FE9 Ῡ */ Letter('Υ', N),
+    /* U+1FEA Ὺ */ Letter('Υ', N),
+    /* U+1FEB Ύ */ Letter('Υ', N),
+    /* U+1FEC Ῥ */ Letter('Ρ', N),
+    /* U+1FED ῭ */ Drop,
+    /* U+1FEE ΅ */ Drop,
+    /* U+1FEF ` */ Drop,
+    /* U+1FF0 ῰ */ Unhandled,
+    /* U+1FF1 ῱ */ Unhandled,
+    /* U+1FF2 ῲ */ Letter('ω', I),
+    /* U+1FF3 ῳ */ Letter('ω', I),
+    /* U+1FF4 ῴ */ Letter('ω', I),
+    /* U+1FF5 ῵ */ Unhandled,
+    /* U+1FF6 ῶ */ Letter('ω', N),
+    /* U+1FF7 ῷ */ Letter('ω', I),
+    /* U+1FF8 Ὸ */ Letter('Ο', N),
+    /* U+1FF9 Ό */ Letter('Ο', N),
+    /* U+1FFA Ὼ */ Letter('Ω', N),
+    /* U+1FFB Ώ */ Letter('Ω', N),
+    /* U+1FFC ῼ */ Letter('Ω', I),
+    /* U+1FFD ´ */ Drop,
+    /* U+1FFE ῾ */ Drop,
+    /* U+1FFF ῿ */ Unhandled,
+];
@@ -0,0 +1,482 @@
+//! Import of the TEI XML transcriptions of manuscripts, as made by the
+//! IGNTP and the INTF.
+//!
+//! A transcription gives the text of the first hand of the manuscript and
+//! the corrections of the later hands (`<app>` with a `<rdg type="orig">`
+//! and one `<rdg type="corr" hand="…">` per correction). [`read`] gives
+//! the text of each hand as a [`Witness`], whose verses are made of
+//! typed [`Segment`]s : the letters the editor supplied, the unclear
+//! letters, the nomina sacra, the lacunae and the line, column and page
+//! breaks are kept apart from the plain text.
+//! # Example :
+//! ```
+//! use gnt_tools::tei::{read, Segment};
+//!
+//! let xml = r#"<TEI><text><body><ab n="B03K12V16">
+//!   <w>ειπεν</w> <w>δε</w> <w>παραβολην</w>
+//!   <w><app><rdg type="orig" hand="firsthand">προϲ</rdg>
+//!           <rdg type="corr" hand="corrector1">εἰϲ</rdg></app></w>
+//!   <w>αυ<supplied reason="lacuna">τουϲ</supplied></w>
+//! </ab></body></text></TEI>"#;
+//!
+//! let witnesses = read(xml).unwrap();
+//! let (firsthand, corrector) = (&witnesses[0], &witnesses[1]);
+//!
+//! assert_eq!(firsthand.hand, "firsthand");
+//! assert_eq!(corrector.hand, "corrector1");
+//! assert_eq!(firsthand.verses[0].reference.unwrap().to_string(), "Luke 12:16");
+//! assert_eq!(firsthand.verses[0].segments.last(),
+//!            Some(&Segment::Supplied("τουϲ".to_string())));
+//!
+//! assert_eq!(firsthand.verses[0].core_text().unwrap(), "ειπενδεπαραβοληνπροϲαυ");
+//! assert_eq!(corrector.verses[0].core_text().unwrap(), "ειπενδεπαραβοληνειϲαυ");
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use roxmltree::{Document, Node};
+
+use crate::reference::{Book, Reference};
+use crate::{try_core_char, UnhandledGreekChar};
+
+/// The hand of the text of the manuscript before any correction.
+pub const FIRST_HAND: &str = "firsthand";
+
+/// A TEI document that cannot be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TeiError {
+    /// The document is not well-formed XML.
+    Xml(String),
+}
+
+impl fmt::Display for TeiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TeiError::Xml(e) => write!(f, "invalid TEI document : {e}"),
+        }
+    }
+}
+
+impl Error for TeiError {}
+
+/// The text of a manuscript as read by one of its hands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    /// The hand, e.g. `firsthand` or `corrector1`.
+    pub hand: String,
+    /// The verses of the manuscript, in the order of the transcription.
+    pub verses: Vec<WitnessVerse>,
+}
+
+/// A verse (`<ab>`) of a [`Witness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitnessVerse {
+    /// The `n` attribute of the verse, e.g. `B03K12V16`.
+    pub id: String,
+    /// The verse of `id`, if it is one of the New Testament.
+    pub reference: Option<Reference>,
+    /// The text of the verse. The words are separated by a space.
+    pub segments: Vec<Segment>,
+}
+
+/// A part of a [`WitnessVerse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Letters read in the manuscript.
+    Text(String),
+    /// Letters the manuscript does not have (anymore), supplied by the
+    /// editor (`<supplied>`).
+    Supplied(String),
+    /// Letters of the manuscript that cannot be read for sure
+    /// (`<unclear>`).
+    Unclear(String),
+    /// A nomen sacrum (`<abbr type="nomSac">`), e.g. `κϲ`.
+    NominaSacra(String),
+    /// A lacuna, or letters that cannot be read at all (`<gap>`).
+    Gap(Gap),
+    /// A line, column or page break.
+    Break(Break),
+}
+
+/// A `<gap>` of a transcription.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gap {
+    /// Why the text is missing, e.g. `lacuna` or `illegible`.
+    pub reason: Option<String>,
+    /// The unit of `extent`, e.g. `char`, `line` or `verse`.
+    pub unit: Option<String>,
+    /// How many units are missing, if known.
+    pub extent: Option<u32>,
+}
+
+/// A break of the layout of a manuscript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Break {
+    /// A line break (`<lb/>`).
+    Line,
+    /// A column break (`<cb/>`).
+    Column,
+    /// A page break (`<pb/>`).
+    Page,
+}
+
+impl Segment {
+    /// The letters of the segment; empty for a gap or a break.
+    pub fn text(&self) -> &str {
+        match self {
+            Segment::Text(s) | Segment::Supplied(s)
+            | Segment::Unclear(s) | Segment::NominaSacra(s) => s,
+            Segment::Gap(_) | Segment::Break(_) => "",
+        }
+    }
+
+    /// The same segment, with its letters given by
+    /// [`core_char`](crate::core_char).
+    pub fn core(&self) -> Result<Segment, UnhandledGreekChar> {
+        let core = |s: &str| -> Result<String, UnhandledGreekChar> {
+            s.chars()
+             .filter_map(|c| try_core_char(&c).transpose())
+             .collect()
+        };
+
+        Ok(match self {
+            Segment::Text(s) => Segment::Text(core(s)?),
+            Segment::Supplied(s) => Segment::Supplied(core(s)?),
+            Segment::Unclear(s) => Segment::Unclear(core(s)?),
+            Segment::NominaSacra(s) => Segment::NominaSacra(core(s)?),
+            Segment::Gap(_) | Segment::Break(_) => self.clone(),
+        })
+    }
+}
+
+impl WitnessVerse {
+    /// The letters of the verse, supplied ones included.
+    pub fn text(&self) -> String {
+        self.segments.iter().map(Segment::text).collect()
+    }
+
+    /// The segments of the verse with their letters given by
+    /// [`core_char`](crate::core_char). The segments left empty are
+    /// dropped.
+    pub fn core_segments(&self) -> Result<Vec<Segment>, UnhandledGreekChar> {
+        let mut segments = Vec::new();
+        for segment in &self.segments {
+            let core = segment.core()?;
+            if !matches!(core, Segment::Text(_) | Segment::Supplied(_)
+                               | Segment::Unclear(_) | Segment::NominaSacra(_))
+               || !core.text().is_empty() {
+                segments.push(core);
+            }
+        }
+
+        Ok(segments)
+    }
+
+    /// The core text of the letters found in the manuscript, i.e. without
+    /// the supplied letters.
+    pub fn core_text(&self) -> Result<String, UnhandledGreekChar> {
+        Ok(self.core_segments()?
+               .iter()
+               .filter(|s| !matches!(s, Segment::Supplied(_)))
+               .map(Segment::text)
+               .collect())
+    }
+}
+
+/// The kind of the letters being read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Text,
+    Supplied,
+    Unclear,
+    NominaSacra,
+}
+
+/// Reads the transcription `xml` into one [`Witness`] per hand, the
+/// first hand first, then the correctors in the order they first
+/// appear.
+///
+/// The text of a corrector is the text of the first hand with the
+/// corrections of this corrector; the notes are left out. The breaks
+/// found before a verse are given with it.
+pub fn read(xml: &str) -> Result<Vec<Witness>, TeiError> {
+    let doc = Document::parse(xml).map_err(|e| TeiError::Xml(e.to_string()))?;
+
+    let mut hands = vec![FIRST_HAND.to_string()];
+    for rdg in doc.descendants().filter(|n| is(*n, "rdg")) {
+        if let Some(hand) = rdg.attribute("hand") {
+            if !hands.iter().any(|h| h == hand) {
+                hands.push(hand.to_string());
+            }
+        }
+    }
+
+    Ok(hands.into_iter()
+            .map(|hand| {
+                let mut reader = Reader {
+                    hand: &hand,
+                    verses: Vec::new(),
+                    pending: Vec::new(),
+                    in_verse: false,
+                };
+                reader.walk(doc.root(), Kind::Text);
+                Witness { verses: reader.verses, hand }
+            })
+            .collect())
+}
+
+struct Reader<'h> {
+    hand: &'h str,
+    verses: Vec<WitnessVerse>,
+    /// The segments read outside of a verse, waiting for the next one.
+    pending: Vec<Segment>,
+    /// Whether the segments read go to the last verse.
+    in_verse: bool,
+}
+
+impl Reader<'_> {
+    fn walk(&mut self, node: Node, kind: Kind) {
+        for child in node.children() {
+            if child.is_text() {
+                self.text(child.text().unwrap_or_default(), kind);
+                continue;
+            }
+            if !child.is_element() {
+                continue;
+            }
+
+            match child.tag_name().name() {
+                "ab" => {
+                    let id = child.attribute("n").unwrap_or_default();
+                    let mut segments = std::mem::take(&mut self.pending);
+                    segments.retain(|s| matches!(s, Segment::Break(_)));
+                    self.verses.push(WitnessVerse {
+                        id: id.to_string(),
+                        reference: reference(id),
+                        segments,
+                    });
+                    self.in_verse = true;
+                    self.walk(child, Kind::Text);
+                    self.in_verse = false;
+                    trim(&mut self.verses.last_mut().unwrap().segments);
+                }
+                "w" => {
+                    self.walk(child, kind);
+                    self.text(" ", kind);
+                }
+                "supplied" => self.walk(child, Kind::Supplied),
+                "unclear" => self.walk(child, Kind::Unclear),
+                "abbr" if child.attribute("type") == Some("nomSac") => {
+                    self.walk(child, Kind::NominaSacra)
+                }
+                "gap" => self.push(Segment::Gap(Gap {
+                    reason: child.attribute("reason").map(str::to_string),
+                    unit: child.attribute("unit").map(str::to_string),
+                    extent: child.attribute("extent").and_then(|e| e.parse().ok()),
+                })),
+                "lb" => self.push(Segment::Break(Break::Line)),
+                "cb" => self.push(Segment::Break(Break::Column)),
+                "pb" => self.push(Segment::Break(Break::Page)),
+                "app" => {
+                    if let Some(rdg) = self.reading(child) {
+                        self.walk(rdg, kind);
+                    }
+                }
+                "note" | "teiHeader" => (),
+                _ => self.walk(child, kind),
+            }
+        }
+    }
+
+    /// The reading of the hand among the readings of `app` : its
+    /// correction if there is one, the text of the first hand otherwise.
+    fn reading<'a, 'i>(&self, app: Node<'a, 'i>) -> Option<Node<'a, 'i>> {
+        let rdgs = || app.children().filter(|n| is(*n, "rdg"));
+        let first_hand = |n: &Node| n.attribute("type") == Some("orig")
+                                    || n.attribute("hand") == Some(FIRST_HAND);
+
+        rdgs().find(|n| n.attribute("hand") == Some(self.hand))
+              .or_else(|| rdgs().find(first_hand))
+              .or_else(|| rdgs().next())
+    }
+
+    fn segments(&mut self) -> &mut Vec<Segment> {
+        match self.verses.last_mut() {
+            Some(verse) if self.in_verse => &mut verse.segments,
+            _ => &mut self.pending,
+        }
+    }
+
+    fn push(&mut self, segment: Segment) {
+        self.segments().push(segment);
+    }
+
+    /// Adds the letters `s`, with their whitespace runs taken as a single
+    /// space, to the segments.
+    fn text(&mut self, s: &str, kind: Kind) {
+        let segments = self.segments();
+        for (i, word) in s.split(char::is_whitespace).enumerate() {
+            let ends_with_space = segments.iter()
+                                          .rev()
+                                          .find(|s| !s.text().is_empty())
+                                          .is_none_or(|s| s.text().ends_with(' '));
+            if i > 0 && !ends_with_space {
+                push_str(segments, " ", Kind::Text);
+            }
+            push_str(segments, word, kind);
+        }
+    }
+}
+
+/// Adds `s` to the last segment of `segments` if it is of the kind
+/// `kind`, to a new segment otherwise.
+fn push_str(segments: &mut Vec<Segment>, s: &str, kind: Kind) {
+    if s.is_empty() {
+        return;
+    }
+    match (segments.last_mut(), kind) {
+        (Some(Segment::Text(t)), Kind::Text)
+        | (Some(Segment::Supplied(t)), Kind::Supplied)
+        | (Some(Segment::Unclear(t)), Kind::Unclear)
+        | (Some(Segment::NominaSacra(t)), Kind::NominaSacra) => t.push_str(s),
+        _ => segments.push(match kind {
+            Kind::Text => Segment::Text(s.to_string()),
+            Kind::Supplied => Segment::Supplied(s.to_string()),
+            Kind::Unclear => Segment::Unclear(s.to_string()),
+            Kind::NominaSacra => Segment::NominaSacra(s.to_string()),
+        }),
+    }
+}
+
+/// Drops the spaces at the start and at the end of the verse.
+fn trim(segments: &mut Vec<Segment>) {
+    if let Some(Segment::Text(t)) = segments.iter_mut()
+                                            .find(|s| !s.text().is_empty()) {
+        *t = t.trim_start().to_string();
+    }
+    if let Some(Segment::Text(t)) = segments.iter_mut()
+                                            .rev()
+                                            .find(|s| !s.text().is_empty()) {
+        *t = t.trim_end().to_string();
+    }
+    segments.retain(|s| !matches!(s, Segment::Text(t) if t.is_empty()));
+}
+
+fn is(node: Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+/// The verse of an IGNTP id, e.g. `B04K12V16` (the books are numbered
+/// from `B01`, Matthew), or of an OSIS one, e.g. `Luke.12.16`.
+fn reference(id: &str) -> Option<Reference> {
+    let igntp = || {
+        let rest = id.strip_prefix('B')?;
+        let (book, rest) = rest.split_once('K')?;
+        let (chapter, verse) = rest.split_once('V')?;
+        let book = *Book::ALL.get(book.parse::<usize>().ok()?.checked_sub(1)?)?;
+
+        Some(Reference::new(book, chapter.parse().ok()?, verse.parse().ok()?))
+    };
+
+    igntp().or_else(|| id.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<TEI xmlns="http://www.tei-c.org/ns/1.0">
+<teiHeader><fileDesc><titleStmt><title>03</title></titleStmt></fileDesc></teiHeader>
+<text><body>
+<pb n="1r"/><cb n="1"/><lb n="1"/>
+<div type="book" n="B04"><div type="chapter" n="B04K1">
+<ab n="B04K1V1">
+  <w>εν</w> <w>αρχη</w> <w>ην</w> <w>ο</w> <w>λογοϲ</w> <lb n="2"/>
+  <w>και</w> <w>ο</w> <w>λογοϲ</w> <w>ην</w> <w>προϲ</w> <w>τον</w>
+  <w><abbr type="nomSac"><hi rend="overline">θν</hi></abbr></w>
+</ab>
+<ab n="B04K1V2">
+  <w>ουτοϲ</w> <w><unclear>η</unclear>ν</w> <gap reason="lacuna" unit="char" extent="2"/>
+  <w><supplied reason="lacuna">αρ</supplied>χη</w>
+  <w><app><rdg type="orig" hand="firsthand">πρ<unclear>ο</unclear>ϲ</rdg>
+    <rdg type="corr" hand="corrector1"><supplied>ε</supplied>ιϲ</rdg>
+    <rdg type="corr" hand="corrector2">παρα</rdg></app></w>
+  <w>τον</w><note>margin</note>
+  <w><abbr type="nomSac"><hi rend="overline">θν</hi></abbr></w>
+</ab>
+</div></div>
+</body></text></TEI>"#;
+
+    #[test]
+    fn test_read() {
+        let witnesses = read(XML).unwrap();
+        let hands : Vec<_> = witnesses.iter().map(|w| w.hand.as_str()).collect();
+        assert_eq!(hands, ["firsthand", "corrector1", "corrector2"]);
+
+        let verses = &witnesses[0].verses;
+        assert_eq!(verses.len(), 2);
+        assert_eq!(verses[0].reference, "John 1:1".parse().ok());
+        assert_eq!(verses[0].segments[..4], [
+            Segment::Break(Break::Page),
+            Segment::Break(Break::Column),
+            Segment::Break(Break::Line),
+            Segment::Text("εν αρχη ην ο λογοϲ ".to_string()),
+        ]);
+        assert_eq!(verses[0].segments.last(),
+                   Some(&Segment::NominaSacra("θν".to_string())));
+
+        assert_eq!(verses[1].segments, [
+            Segment::Text("ουτοϲ ".to_string()),
+            Segment::Unclear("η".to_string()),
+            Segment::Text("ν ".to_string()),
+            Segment::Gap(Gap {
+                reason: Some("lacuna".to_string()),
+                unit: Some("char".to_string()),
+                extent: Some(2),
+            }),
+            Segment::Supplied("αρ".to_string()),
+            Segment::Text("χη πρ".to_string()),
+            Segment::Unclear("ο".to_string()),
+            Segment::Text("ϲ τον ".to_string()),
+            Segment::NominaSacra("θν".to_string()),
+        ]);
+        assert_eq!(verses[1].text(), "ουτοϲ ην αρχη προϲ τον θν");
+    }
+
+    #[test]
+    fn test_hands() {
+        let witnesses = read(XML).unwrap();
+        let core : Vec<_> = witnesses.iter()
+                                     .map(|w| w.verses[1].core_text().unwrap())
+                                     .collect();
+
+        assert_eq!(core, ["ουτοϲηνχηπροϲτονθν",
+                          "ουτοϲηνχηιϲτονθν",
+                          "ουτοϲηνχηπαρατονθν"]);
+        // The verses without correction are the same for every hand.
+        assert_eq!(witnesses[0].verses[0], witnesses[2].verses[0]);
+    }
+
+    #[test]
+    fn test_core_segments() {
+        let verse = WitnessVerse {
+            id: "B04K1V1".to_string(),
+            reference: reference("B04K1V1"),
+            segments: vec![Segment::Text("Ἐν ".to_string()),
+                           Segment::Break(Break::Line),
+                           Segment::Supplied("ἀρ".to_string()),
+                           Segment::Text("·".to_string())],
+        };
+
+        assert_eq!(verse.core_segments().unwrap(), [
+            Segment::Text("εν".to_string()),
+            Segment::Break(Break::Line),
+            Segment::Supplied("αρ".to_string()),
+        ]);
+        assert_eq!(reference("Luke.12.16"), "Lk 12:16".parse().ok());
+        assert_eq!(reference("B28K1V1"), None);
+        assert!(matches!(read("<TEI>"), Err(TeiError::Xml(_))));
+    }
+}
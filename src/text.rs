@@ -0,0 +1,348 @@
+use std::fmt;
+use std::ops::Range;
+
+use unicode_normalization::char::compose;
+
+use crate::profile::MACRON;
+use crate::removed::categorize;
+use crate::{is_combining, unhandled_panic, CoreEngine, RemovedCategory,
+            UnhandledGreekChar};
+
+/// What to do when the core normalization meets a character of the
+/// Greek block it does not handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Policy {
+    /// Panic, like [`core_char`](crate::core_char) does.
+    Panic,
+    /// Stop at the first unhandled character and return it as an error.
+    #[default]
+    Strict,
+    /// Drop the unhandled character, record it, and keep going.
+    Lenient,
+}
+
+/// The part of the source string a letter of a [`CoreText`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Byte offset of the first source character.
+    pub start: usize,
+    /// Byte offset just past the last source character.
+    pub end: usize,
+    /// Index, counted in `char`s, of the first source character.
+    pub char_start: usize,
+    /// Index, counted in `char`s, just past the last source character.
+    pub char_end: usize,
+}
+
+impl Span {
+    /// The byte range of the span, usable to slice the source.
+    pub fn bytes(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// The range of the span counted in `char`s.
+    pub fn chars(&self) -> Range<usize> {
+        self.char_start..self.char_end
+    }
+
+    /// The smallest span covering both `self` and `other`.
+    pub fn cover(&self, other: &Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+            char_start: self.char_start.min(other.char_start),
+            char_end: self.char_end.max(other.char_end),
+        }
+    }
+}
+
+/// The core text of a source string, keeping for each of its letters
+/// the [`Span`] of the source it comes from.
+///
+/// This is what allows to take a difference found between two core
+/// texts back to the accented words of the editions they come from.
+/// # Example :
+/// ```
+/// use gnt_tools::core_text;
+///
+/// let s = "16 Εἶπεν δὲ παραβολὴν";
+/// let core = core_text(s).unwrap();
+///
+/// assert_eq!(core.as_str(), "ειπενδεπαραβολην");
+///
+/// // "δε" are the 6th and 7th letters of the core text.
+/// let span = core.source_span(5..7).unwrap();
+/// assert_eq!(&s[span.bytes()], "δὲ");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoreText {
+    text: String,
+    spans: Vec<Span>,
+    word_starts: Vec<usize>,
+    unhandled: Vec<UnhandledGreekChar>,
+}
+
+impl CoreText {
+    /// The core text itself.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Consumes `self` and gives the core text as a `String`.
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// Number of letters of the core text.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Whether the core text has no letter.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// The source spans, one for each letter of the core text.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// The source span of the `i`-th letter of the core text.
+    pub fn span(&self, i: usize) -> Option<Span> {
+        self.spans.get(i).copied()
+    }
+
+    /// The source span covering the letters `letters` of the core text,
+    /// or `None` if the range is empty or out of bounds.
+    pub fn source_span(&self, letters: Range<usize>) -> Option<Span> {
+        let spans = self.spans.get(letters)?;
+        let first = spans.first()?;
+        Some(spans.iter().fold(*first, |acc, s| acc.cover(s)))
+    }
+
+    /// Number of words of the core text.
+    pub fn word_count(&self) -> usize {
+        self.word_starts.len()
+    }
+
+    /// The words of the core text, each as its range of letters and its
+    /// text.
+    ///
+    /// Words are delimited in the source by whitespace, punctuation and
+    /// digits. The characters used to encode nomina sacra (e.g. `|κς|`)
+    /// do not delimit words.
+    /// # Example :
+    /// ```
+    /// use gnt_tools::core_text;
+    ///
+    /// let core = core_text("λέγων· τί ποιήσω,").unwrap();
+    /// let words : Vec<_> = core.words().map(|(_, w)| w).collect();
+    ///
+    /// assert_eq!(words, ["λεγων", "τι", "ποιηϲω"]);
+    /// ```
+    pub fn words(&self) -> impl Iterator<Item = (Range<usize>, &str)> + '_ {
+        let ends = self.word_starts.iter()
+                                   .skip(1)
+                                   .copied()
+                                   .chain(std::iter::once(self.len()));
+        let mut rest = self.text.as_str();
+
+        self.word_starts.iter().zip(ends).map(move |(&start, end)| {
+            let n = rest.chars()
+                        .take(end - start)
+                        .map(char::len_utf8)
+                        .sum();
+            let (word, tail) = rest.split_at(n);
+            rest = tail;
+            (start..end, word)
+        })
+    }
+
+    /// The unhandled characters dropped with [`Policy::Lenient`].
+    pub fn unhandled(&self) -> &[UnhandledGreekChar] {
+        &self.unhandled
+    }
+
+    /// Appends `c`, making it start a new word if `new_word` is true.
+    pub(crate) fn push(&mut self, c: char, span: Span, new_word: bool) {
+        if new_word || self.word_starts.is_empty() {
+            self.word_starts.push(self.len());
+        }
+        self.text.push(c);
+        self.spans.push(span);
+    }
+
+    /// Composes the last letter with the combining `mark`, found at
+    /// `span`, if they compose. Its span is widened to the mark.
+    pub(crate) fn compose_last(&mut self, mark: char, span: Span) -> bool {
+        let Some(last) = self.text.chars().next_back() else {
+            return false;
+        };
+        let Some(composed) = compose(last, mark) else {
+            return false;
+        };
+        self.text.truncate(self.text.len() - last.len_utf8());
+        self.text.push(composed);
+        let last_span = self.spans.last_mut().unwrap();
+        last_span.end = span.end;
+        last_span.char_end = span.char_end;
+
+        true
+    }
+
+    /// Appends `letters` as a new word.
+    pub(crate) fn push_word(&mut self,
+                            letters: impl Iterator<Item = (char, Span)>) {
+        for (i, (c, span)) in letters.enumerate() {
+            self.push(c, span, i == 0);
+        }
+    }
+
+    /// An empty core text keeping the unhandled characters of `self`,
+    /// to build a new core text from it.
+    pub(crate) fn empty_like(&self) -> CoreText {
+        CoreText {
+            text: String::with_capacity(self.text.len()),
+            spans: Vec::with_capacity(self.spans.len()),
+            word_starts: Vec::with_capacity(self.word_starts.len()),
+            unhandled: self.unhandled.clone(),
+        }
+    }
+}
+
+impl fmt::Display for CoreText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl AsRef<str> for CoreText {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl PartialEq<str> for CoreText {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for CoreText {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
+
+/// Gives the core text of `s`, i.e. `s` with [`core_char`](crate::core_char)
+/// applied to each of its characters.
+///
+/// Stops at the first unhandled character and returns it, with its
+/// byte offset in `s`.
+/// # Example :
+/// ```
+/// use gnt_tools::core_text;
+///
+/// assert_eq!(core_text("16 Εἶπεν δὲ παραβολὴν").unwrap(),
+///            "ειπενδεπαραβολην");
+/// assert_eq!(core_text("λόγος ϣ").unwrap_err().offset, 11);
+/// ```
+pub fn core_text(s: &str) -> Result<CoreText, UnhandledGreekChar> {
+    core_text_with(s, Policy::Strict)
+}
+
+/// Gives the core text of `s` following `policy`.
+///
+/// With [`Policy::Lenient`], the unhandled characters met on the way
+/// are available through [`CoreText::unhandled`]; an error can only be
+/// returned with [`Policy::Strict`].
+/// # Example :
+/// ```
+/// use gnt_tools::{core_text_with, Policy};
+///
+/// let core = core_text_with("λόγος ϣ", Policy::Lenient).unwrap();
+///
+/// assert_eq!(core, "λογοϲ");
+/// assert_eq!(core.unhandled()[0].ch, 'ϣ');
+/// ```
+pub fn core_text_with(s: &str, policy: Policy)
+    -> Result<CoreText, UnhandledGreekChar> {
+    core_text_engine(s, CoreEngine::default_ref(), policy)
+}
+
+pub(crate) fn core_text_engine(s: &str, engine: &CoreEngine, policy: Policy)
+    -> Result<CoreText, UnhandledGreekChar> {
+
+    let mut core = CoreText {
+        text: String::with_capacity(s.len()),
+        spans: Vec::with_capacity(s.len() / 2),
+        word_starts: Vec::new(),
+        unhandled: Vec::new(),
+    };
+
+    let mut new_word = true;
+    // Whether the last letter, and the one before it, have a macron.
+    let mut macron = (false, false);
+    // Whether the last character is a letter or one of its marks.
+    let mut in_letter = false;
+
+    for (char_start, (offset, c)) in s.char_indices().enumerate() {
+        let span = Span {
+            start: offset,
+            end: offset + c.len_utf8(),
+            char_start,
+            char_end: char_start + 1,
+        };
+        let core_c = engine.try_core_char(&c);
+        // A letter never separates words, which spares the test for most
+        // characters.
+        if !matches!(core_c, Ok(Some(_))) && is_word_separator(c) {
+            new_word = true;
+        }
+        in_letter &= is_combining(c);
+        if in_letter && engine.profile().keeps_mark(c) && core.compose_last(c, span) {
+            continue;
+        }
+        if c == MACRON && engine.profile().is_suspended_nu() && !new_word && !macron.0 {
+            macron.0 = true;
+            if !macron.1 && ends_word(&s[span.end..]) {
+                core.push('ν', span, false);
+                continue;
+            }
+        }
+        match core_c {
+            Ok(Some(core_c)) => {
+                core.push(core_c, span, new_word);
+                new_word = false;
+                in_letter = true;
+                macron = (false, macron.0);
+            }
+            Ok(None) => (),
+            Err(e) => {
+                let e = UnhandledGreekChar { offset, ..e };
+                match policy {
+                    Policy::Panic => unhandled_panic(e.ch),
+                    Policy::Strict => return Err(e),
+                    Policy::Lenient => core.unhandled.push(e),
+                }
+            }
+        }
+    }
+
+    Ok(core)
+}
+
+/// Whether the source `rest` following a letter starts by the end of its
+/// word, combining marks aside.
+fn ends_word(rest: &str) -> bool {
+    rest.chars()
+        .find(|c| !is_combining(*c))
+        .is_none_or(is_word_separator)
+}
+
+pub(crate) fn is_word_separator(c: char) -> bool {
+    c.is_whitespace()
+    || c.is_numeric()
+    || categorize(c) == RemovedCategory::Punctuation
+}
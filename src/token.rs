@@ -0,0 +1,349 @@
+//! Tokenization of a source string into core letters, and the nomina sacra
+//! and numerals its transcription marks as such.
+//!
+//! The core normalization deletes the characters used to encode nomina
+//! sacra and numerals, so `|κς|` gives `κϲ`, `ιβʹ` gives `ιβ`, and nothing
+//! tells anymore what they were. [`tokenize`] keeps this information.
+//! # Example :
+//! ```
+//! use gnt_tools::token::{tokenize, Marker, Token};
+//!
+//! let tokens = tokenize("ὁ |κς|").unwrap();
+//!
+//! assert!(matches!(tokens[0], Token::Letter('ο', _)));
+//! match &tokens[1] {
+//!     Token::NominaSacra(ns) => {
+//!         assert_eq!(ns.core, "κϲ");
+//!         assert_eq!(ns.marker, Marker::Bars);
+//!         assert_eq!(ns.full(), Some("κυριοϲ"));
+//!     }
+//!     _ => unreachable!(),
+//! }
+//! ```
+
+use crate::nomina_sacra::expand_marked_word;
+use crate::numeral::{is_keraia, letter_value, parse_values, LOWER_KERAIA};
+use crate::profile::MACRON;
+use crate::{is_combining, try_core_char, Span, UnhandledGreekChar};
+
+const OVERLINE: char = '\u{0305}';
+
+/// How a nomen sacrum is marked in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Marker {
+    /// Between vertical bars, e.g. `|κς|`.
+    Bars,
+    /// Between parentheses, e.g. `(κς)`.
+    Parentheses,
+    /// With a combining overline (U+0305) over its letters, e.g. `κ̅ς̅`.
+    Overline,
+    /// With a combining macron (U+0304) over at least two of its
+    /// letters, e.g. `κ̄ς̄`. A macron over a single letter is not taken
+    /// as a nomen sacrum marker since it often stands for a final nu.
+    Macron,
+}
+
+/// A nomen sacrum marked as such in the source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MarkedNomenSacrum {
+    /// The core text of the nomen sacrum, e.g. `κϲ`.
+    pub core: String,
+    /// How it is marked.
+    pub marker: Marker,
+    /// The source span of the nomen sacrum, markers included.
+    pub span: Span,
+}
+
+impl MarkedNomenSacrum {
+    /// The full form of the nomen sacrum, if it is a known contracted form.
+    pub fn full(&self) -> Option<&'static str> {
+        expand_marked_word(&self.core)
+    }
+}
+
+/// A token of a source string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Token {
+    /// A letter of the core text, with its source span.
+    Letter(char, Span),
+    /// A nomen sacrum marked as such in the source.
+    NominaSacra(MarkedNomenSacrum),
+    /// A numeral marked as such in the source, with its value and source
+    /// span : letters followed by a keraia (`ιβʹ`), a letter preceded by a
+    /// lower keraia (`͵α`, possibly followed by a numeral with a keraia), or
+    /// overlined letters that are not a known nomen sacrum.
+    Numeral(u32, Span),
+}
+
+impl Token {
+    /// The source span of the token.
+    pub fn span(&self) -> Span {
+        match self {
+            Token::Letter(_, span) | Token::Numeral(_, span) => *span,
+            Token::NominaSacra(ns) => ns.span,
+        }
+    }
+}
+
+/// A base character with the combining marks following it.
+struct Cluster {
+    span: Span,
+    base: char,
+    core: Option<char>,
+    /// The numeric value of the letter, if it is one.
+    value: Option<u32>,
+    overline: bool,
+    macron: bool,
+}
+
+impl Cluster {
+    fn is_letter(&self) -> bool {
+        self.core.is_some() || self.value.is_some()
+    }
+}
+
+enum Found {
+    NominaSacra(Marker),
+    Numeral(u32),
+}
+
+/// Splits `s` into core letters, marked nomina sacra and marked numerals.
+///
+/// Stops at the first unhandled character of the Greek block and returns
+/// it, with its byte offset in `s`.
+pub fn tokenize(s: &str) -> Result<Vec<Token>, UnhandledGreekChar> {
+    let clusters = clusters(s)?;
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < clusters.len() {
+        let c = &clusters[i];
+
+        let found = match c.base {
+            '|' => delimited(&clusters, i, '|')
+                       .map(|j| (j + 1, Found::NominaSacra(Marker::Bars))),
+            '(' => delimited(&clusters, i, ')')
+                       .map(|j| (j + 1, Found::NominaSacra(Marker::Parentheses))),
+            LOWER_KERAIA => thousands(&clusters, i),
+            _ if c.overline => {
+                let end = run(&clusters, i, |c| c.overline);
+                let core : String = clusters[i..end].iter()
+                                                    .filter_map(|c| c.core)
+                                                    .collect();
+                match numeral(&clusters[i..end]) {
+                    Some(v) if expand_marked_word(&core).is_none() => {
+                        Some((end, Found::Numeral(v)))
+                    }
+                    _ => Some((end, Found::NominaSacra(Marker::Overline))),
+                }
+            }
+            _ if c.macron => Some((run(&clusters, i, |c| c.macron),
+                                   Found::NominaSacra(Marker::Macron)))
+                                .filter(|(j, _)| j - i >= 2),
+            _ if c.value.is_some()
+                 && (i == 0 || !clusters[i - 1].is_letter()) => {
+                let end = run(&clusters, i, |_| true);
+                clusters.get(end)
+                        .filter(|k| is_keraia(k.base))
+                        .and_then(|_| numeral(&clusters[i..end]))
+                        .map(|v| (end + 1, Found::Numeral(v)))
+            }
+            _ => None,
+        };
+
+        match found {
+            Some((end, found)) => {
+                let marked = &clusters[i..end];
+                let span = marked[0].span.cover(&marked[marked.len() - 1].span);
+                tokens.push(match found {
+                    Found::NominaSacra(marker) => {
+                        Token::NominaSacra(MarkedNomenSacrum {
+                            core: marked.iter().filter_map(|c| c.core).collect(),
+                            marker,
+                            span,
+                        })
+                    }
+                    Found::Numeral(v) => Token::Numeral(v, span),
+                });
+                i = end;
+            }
+            None => {
+                if let Some(l) = c.core {
+                    tokens.push(Token::Letter(l, c.span));
+                }
+                i += 1;
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// The value of the numeral written with the letters of `clusters`.
+fn numeral(clusters: &[Cluster]) -> Option<u32> {
+    let values : Option<Vec<u32>> = clusters.iter().map(|c| c.value).collect();
+    parse_values(values?)
+}
+
+/// The numeral starting with the lower keraia at `i`.
+fn thousands(clusters: &[Cluster], i: usize) -> Option<(usize, Found)> {
+    let v = clusters.get(i + 1)?.value.filter(|v| *v < 10)? * 1000;
+    let end = run(clusters, i + 2, |_| true);
+
+    match clusters.get(end) {
+        Some(k) if is_keraia(k.base) && end == i + 2 => {
+            Some((end + 1, Found::Numeral(v)))
+        }
+        Some(k) if is_keraia(k.base) => {
+            match numeral(&clusters[i + 2..end]) {
+                Some(rest) => Some((end + 1, Found::Numeral(v + rest))),
+                None => Some((i + 2, Found::Numeral(v))),
+            }
+        }
+        _ => Some((i + 2, Found::Numeral(v))),
+    }
+}
+
+/// The index of the cluster closing the delimiter opened at `open`,
+/// if only letters are in between.
+fn delimited(clusters: &[Cluster], open: usize, close: char) -> Option<usize> {
+    let inner = clusters[open + 1..].iter()
+                                    .take_while(|c| c.is_letter())
+                                    .count();
+    let j = open + 1 + inner;
+
+    (inner > 0 && clusters.get(j)?.base == close).then_some(j)
+}
+
+/// The end of the run of letters starting at `start` and satisfying `p`.
+fn run(clusters: &[Cluster], start: usize, p: impl Fn(&Cluster) -> bool)
+    -> usize {
+    start + clusters[start..].iter()
+                             .take_while(|c| c.is_letter() && p(c))
+                             .count()
+}
+
+fn clusters(s: &str) -> Result<Vec<Cluster>, UnhandledGreekChar> {
+    let mut clusters : Vec<Cluster> = Vec::with_capacity(s.len() / 2);
+
+    for (char_start, (offset, c)) in s.char_indices().enumerate() {
+        let core = try_core_char(&c).map_err(|e| UnhandledGreekChar {
+            offset,
+            ..e
+        })?;
+        let span = Span {
+            start: offset,
+            end: offset + c.len_utf8(),
+            char_start,
+            char_end: char_start + 1,
+        };
+
+        match clusters.last_mut() {
+            Some(last) if is_combining(c) => {
+                last.span = last.span.cover(&span);
+                last.overline |= c == OVERLINE;
+                last.macron |= c == MACRON;
+            }
+            _ => clusters.push(Cluster {
+                span,
+                base: c,
+                core,
+                value: core.and_then(letter_value),
+                overline: false,
+                macron: false,
+            }),
+        }
+    }
+
+    Ok(clusters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marked(s: &str) -> Vec<(String, Marker, &str)> {
+        tokenize(s).unwrap()
+                   .into_iter()
+                   .filter_map(|t| match t {
+                       Token::NominaSacra(ns) => {
+                           Some((ns.core, ns.marker, &s[ns.span.bytes()]))
+                       }
+                       Token::Letter(..) | Token::Numeral(..) => None,
+                   })
+                   .collect()
+    }
+
+    #[test]
+    fn test_markers() {
+        let s = "ὁ |κς| (θυ) κ\u{305}ς\u{305} ι\u{304}η\u{304}υ\u{304}";
+
+        assert_eq!(marked(s), [
+            ("κϲ".to_string(), Marker::Bars, "|κς|"),
+            ("θυ".to_string(), Marker::Parentheses, "(θυ)"),
+            ("κϲ".to_string(), Marker::Overline, "κ\u{305}ς\u{305}"),
+            ("ιηυ".to_string(), Marker::Macron, "ι\u{304}η\u{304}υ\u{304}"),
+        ]);
+
+        // A marked contraction that is also a common word.
+        let full : Vec<_> = tokenize("ὁ |υς|").unwrap()
+                                              .iter()
+                                              .filter_map(|t| match t {
+                                                  Token::NominaSacra(ns) => ns.full(),
+                                                  _ => None,
+                                              })
+                                              .collect();
+        assert_eq!(full, ["υιοϲ"]);
+    }
+
+    #[test]
+    fn test_not_markers() {
+        // A single macron, unclosed or non-letter delimiters.
+        let s = "παραβολη\u{304} |κς (1) ( θυ ) λόγος|";
+
+        assert!(marked(s).is_empty());
+
+        let letters : String = tokenize(s).unwrap()
+                                          .iter()
+                                          .map(|t| match t {
+                                              Token::Letter(l, _) => *l,
+                                              _ => unreachable!(),
+                                          })
+                                          .collect();
+        assert_eq!(letters, crate::core_text(s).unwrap().as_str());
+    }
+
+    #[test]
+    fn test_numerals() {
+        let s = "ιβʹ \u{375}α \u{375}ασλδʹ ι\u{305}β\u{305} θ\u{305}υ\u{305} χξϛʹ λόγοςʹ";
+        let numerals : Vec<_> = tokenize(s).unwrap()
+                                           .into_iter()
+                                           .filter_map(|t| match t {
+                                               Token::Numeral(v, span) => {
+                                                   Some((v, &s[span.bytes()]))
+                                               }
+                                               _ => None,
+                                           })
+                                           .collect();
+
+        assert_eq!(numerals, [
+            (12, "ιβʹ"),
+            (1000, "\u{375}α"),
+            (1234, "\u{375}ασλδʹ"),
+            (12, "ι\u{305}β\u{305}"),
+            (666, "χξϛʹ"),
+        ]);
+        assert!(tokenize(s).unwrap().iter().any(|t| matches!(t,
+            Token::NominaSacra(ns) if ns.core == "θυ")));
+    }
+
+    #[test]
+    fn test_spans_and_errors() {
+        let s = "ἐν |ἀνῳ|";
+        let tokens = tokenize(s).unwrap();
+
+        assert_eq!(&s[tokens[0].span().bytes()], "ἐ");
+        assert_eq!(&s[tokens[2].span().bytes()], "|ἀνῳ|");
+        assert_eq!(tokenize("|κς| ϣ").unwrap_err().offset, 7);
+    }
+}
@@ -0,0 +1,339 @@
+//! Import and export of USFM files.
+//!
+//! [`read`] keeps the text of the verses (`\v`) only : the notes and cross
+//! references (`\f …\f*`, `\x …\x*`), the headings and titles (`\s`,
+//! `\mt`, …) are left out, and the markers of the character styles
+//! (`\w …\w*`, `\add …\add*`, …) are dropped with their attributes
+//! (`|lemma="…"`, just before the closing marker). A verse bridging
+//! several ones (`\v 2-3`) keeps its range in [`Verse::last`].
+//! # Example :
+//! ```
+//! use gnt_tools::usfm;
+//!
+//! let s = r#"\id LUK SBLGNT
+//! \c 12
+//! \s1 Ἡ παραβολὴ τοῦ ἄφρονος πλουσίου
+//! \p
+//! \v 16 Εἶπεν δὲ \w παραβολὴν|lemma="παραβολή"\w* πρὸς αὐτοὺς\f + \fr 12:16 \ft note\f* λέγων·"#;
+//!
+//! let books = usfm::read(s).unwrap();
+//! let verse = books[0].verse(12, 16).unwrap();
+//!
+//! assert_eq!(verse.text, "Εἶπεν δὲ παραβολὴν πρὸς αὐτοὺς λέγων·");
+//!
+//! let core = usfm::write_core(&books).unwrap();
+//! assert_eq!(core, "\\id LUK\n\\c 12\n\\p\n\\v 16 ειπενδεπαραβοληνπροϲαυτουϲλεγων\n");
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+
+use crate::reference::Book;
+use crate::verse::{push_text, push_verse, BookText, Verse};
+use crate::UnhandledGreekChar;
+
+/// A USFM file that cannot be read or written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UsfmError {
+    /// A book identifier that is not one of the New Testament.
+    Book(String),
+    /// A chapter or a verse found before the `\id` of its book.
+    MissingId,
+    /// A `\c` or a `\v` marker without a number, or with a range of
+    /// verses not in order.
+    Number(String),
+    /// A verse with an unhandled character, when writing its core text.
+    Unhandled(UnhandledGreekChar),
+}
+
+impl fmt::Display for UsfmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UsfmError::Book(id) => {
+                write!(f, "'{id}' is not a New Testament book")
+            }
+            UsfmError::MissingId => f.write_str("text found before any \\id"),
+            UsfmError::Number(marker) => {
+                write!(f, "\\{marker} is not followed by a number")
+            }
+            UsfmError::Unhandled(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for UsfmError {}
+
+impl From<UnhandledGreekChar> for UsfmError {
+    fn from(e: UnhandledGreekChar) -> UsfmError {
+        UsfmError::Unhandled(e)
+    }
+}
+
+/// The markers whose content, up to the end of the line, is not part of
+/// the text : identification, headings and titles, introductions.
+fn is_heading(marker: &str) -> bool {
+    let name = marker.trim_end_matches(|c: char| c.is_ascii_digit());
+    matches!(name,
+        "ide" | "h" | "toc" | "toca" | "rem" | "sts" | "usfm" | "restore"
+        | "mt" | "mte" | "ms" | "mr" | "s" | "sr" | "r" | "d" | "sp"
+        | "cl" | "cp" | "periph"
+        | "imt" | "imte" | "is" | "ip" | "ipi" | "im" | "imi" | "ipq" | "imq"
+        | "ipr" | "iq" | "ib" | "ili" | "iot" | "io" | "iex" | "ie")
+}
+
+/// The markers whose content, up to their closing marker (e.g. `\f*`), is
+/// not part of the text : notes, cross references, alternate numbers,
+/// figures.
+fn is_note(marker: &str) -> bool {
+    matches!(marker,
+        "f" | "fe" | "ef" | "x" | "ex" | "ca" | "va" | "vp" | "fig")
+}
+
+/// Reads the books of the USFM file `s`. The span of a verse runs from
+/// the first letter of its text to the last one, markers included, and
+/// its pieces map its text back to `s`, see [`Verse::source_range`].
+pub fn read(s: &str) -> Result<Vec<BookText>, UsfmError> {
+    let mut books = Vec::new();
+    let mut book = None;
+    let mut chapter = 0;
+    let mut current : Option<Verse> = None;
+    // The character markers opened in the current verse.
+    let mut open : Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    let finish = |books: &mut Vec<BookText>, book, chapter,
+                  current: &mut Option<Verse>| {
+        if let (Some(book), Some(mut verse)) = (book, current.take()) {
+            verse.text.truncate(verse.text.trim_end().len());
+            push_verse(books, book, chapter, verse);
+        }
+    };
+
+    while i < s.len() {
+        let next = s[i..].find('\\').map_or(s.len(), |j| i + j);
+        let (marker, end) = if next < s.len() { marker(s, next) } else { ("", next) };
+        let marker = marker.trim_start_matches('+');
+
+        // The attributes of a word (`\w λόγος|lemma="λόγος"\w*`) end the
+        // text of a character marker, just before it is closed.
+        let mut text_end = next;
+        if let Some(closing) = marker.strip_suffix('*') {
+            if let Some(k) = open.iter().rposition(|m| *m == closing) {
+                open.truncate(k);
+                if let Some(bar) = s[i..next].rfind('|') {
+                    text_end = i + bar;
+                }
+            }
+        }
+        if let Some(verse) = &mut current {
+            push_text(verse, &s[i..text_end], &s[i..text_end], i);
+        }
+        if next == s.len() {
+            break;
+        }
+
+        i = end;
+        if marker.ends_with('*') {
+            continue;
+        }
+
+        match marker {
+            "id" => {
+                finish(&mut books, book, chapter, &mut current);
+                open.clear();
+                let (id, end) = word(s, i);
+                book = Some(id.parse::<Book>()
+                              .map_err(|_| UsfmError::Book(id.to_string()))?);
+                chapter = 0;
+                i = line_end(s, end);
+            }
+            "c" | "v" => {
+                finish(&mut books, book, chapter, &mut current);
+                open.clear();
+                if book.is_none() {
+                    return Err(UsfmError::MissingId);
+                }
+                let (n, end) = word(s, i);
+                let error = || UsfmError::Number(marker.to_string());
+                // Parts of verses (`\v 2a`) are given their number.
+                let (first, last) = n.split_once('-').unwrap_or((n, n));
+                let number = leading_number(first).ok_or_else(error)?;
+                let last = leading_number(last).ok_or_else(error)?;
+                if last < number {
+                    return Err(error());
+                }
+                i = end;
+                if marker == "c" {
+                    chapter = number;
+                } else {
+                    current = Some(Verse {
+                        number,
+                        last,
+                        text: String::new(),
+                        span: i..i,
+                        pieces: Vec::new(),
+                    });
+                }
+            }
+            _ if is_heading(marker) => i = line_end(s, i),
+            _ if is_note(marker) => {
+                let close = format!("\\{marker}*");
+                i = s[i..].find(&close).map_or(s.len(), |j| i + j + close.len());
+            }
+            _ => open.push(marker),
+        }
+    }
+    finish(&mut books, book, chapter, &mut current);
+
+    Ok(books)
+}
+
+/// The marker starting at `start`, without its backslash, and the
+/// position of the text that follows it.
+fn marker(s: &str, start: usize) -> (&str, usize) {
+    let name = start + 1;
+    let end = s[name..].find(|c: char| !(c.is_ascii_alphanumeric()
+                                         || c == '+' || c == '-'))
+                       .map_or(s.len(), |j| name + j);
+
+    match s[end..].chars().next() {
+        Some('*') => (&s[name..end + 1], end + 1),
+        Some(c) if c.is_whitespace() => (&s[name..end], end + c.len_utf8()),
+        _ => (&s[name..end], end),
+    }
+}
+
+/// The number at the start of `s`, e.g. 2 for `2a`.
+fn leading_number(s: &str) -> Option<u32> {
+    let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s[..digits].parse().ok()
+}
+
+/// The word following `start`, and the position after it.
+fn word(s: &str, start: usize) -> (&str, usize) {
+    let rest = s[start..].trim_start();
+    let begin = s.len() - rest.len();
+    let end = rest.find(char::is_whitespace).map_or(s.len(), |j| begin + j);
+
+    (&s[begin..end], end)
+}
+
+fn line_end(s: &str, start: usize) -> usize {
+    s[start..].find('\n').map_or(s.len(), |j| start + j)
+}
+
+/// Writes `books` as USFM, with one paragraph per chapter.
+pub fn write(books: &[BookText]) -> Result<String, UsfmError> {
+    write_with(books, |verse| Ok(verse.text.clone()))
+}
+
+/// Writes the core text of each verse of `books` as USFM, with one
+/// paragraph per chapter.
+pub fn write_core(books: &[BookText]) -> Result<String, UsfmError> {
+    write_with(books, |verse| Ok(verse.core_text()?.into_string()))
+}
+
+fn write_with(books: &[BookText],
+              text: impl Fn(&Verse) -> Result<String, UsfmError>)
+    -> Result<String, UsfmError> {
+
+    let mut out = String::new();
+    for book in books {
+        let id = book.book()
+                     .map(Book::usfm)
+                     .ok_or_else(|| UsfmError::Book(book.title.clone()))?;
+        writeln!(out, "\\id {id}").unwrap();
+        for chapter in &book.chapters {
+            writeln!(out, "\\c {}\n\\p", chapter.number).unwrap();
+            for verse in &chapter.verses {
+                if verse.last == verse.number {
+                    writeln!(out, "\\v {} {}", verse.number, text(verse)?).unwrap();
+                } else {
+                    writeln!(out, "\\v {}-{} {}", verse.number, verse.last,
+                             text(verse)?).unwrap();
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const S: &str = r#"\id MAT SBLGNT
+\usfm 3.0
+\h ΚΑΤΑ ΜΑΘΘΑΙΟΝ
+\mt1 ΚΑΤΑ ΜΑΘΘΑΙΟΝ
+\c 1
+\p
+\v 1 Βίβλος γενέσεως \nd Ἰησοῦ\nd* Χριστοῦ
+υἱοῦ Δαυὶδ\x - \xo 1:1 \xt Lk 3:23\x* υἱοῦ Ἀβραάμ.
+\v 2 Ἀβραὰμ ἐγέννησεν τὸν Ἰσαάκ,
+\c 2
+\s1 Οἱ μάγοι
+\p
+\v 1 Τοῦ δὲ \+w Ἰησοῦ|strong="G2424"\+w* γεννηθέντος
+\v 2-3 λέγοντες·
+\id MRK
+\c 1
+\v 1 Ἀρχὴ τοῦ εὐαγγελίου
+"#;
+
+    #[test]
+    fn test_read() {
+        let books = read(S).unwrap();
+
+        assert_eq!(books.len(), 2);
+        assert_eq!(books[1].book(), Some(Book::Mark));
+        let refs : Vec<_> = books[0].verses().map(|(c, v)| (c, v.number)).collect();
+        assert_eq!(refs, [(1, 1), (1, 2), (2, 1), (2, 2)]);
+
+        let verse = books[0].verse(1, 1).unwrap();
+        assert_eq!(verse.text, "Βίβλος γενέσεως Ἰησοῦ Χριστοῦ υἱοῦ Δαυὶδ υἱοῦ Ἀβραάμ.");
+        assert!(S[verse.span.clone()].starts_with("Βίβλος"));
+        assert!(S[verse.span.clone()].ends_with("Ἀβραάμ."));
+        let word = verse.text.find("Χριστοῦ").unwrap();
+        assert_eq!(&S[verse.source_range(word..word + "Χριστοῦ".len())], "Χριστοῦ");
+        assert_eq!(books[0].verse(2, 1).unwrap().text, "Τοῦ δὲ Ἰησοῦ γεννηθέντος");
+        assert_eq!(books[0].verse(2, 2).unwrap().last, 3);
+
+        assert_eq!(read("\\id TOB\n"), Err(UsfmError::Book("TOB".to_string())));
+        assert_eq!(read("\\c 1\n"), Err(UsfmError::MissingId));
+        assert_eq!(read("\\id MAT\n\\v a"), Err(UsfmError::Number("v".to_string())));
+        assert_eq!(read("\\id MAT\n\\v 3-2"), Err(UsfmError::Number("v".to_string())));
+    }
+
+    #[test]
+    fn test_bars() {
+        // A bar outside of a character marker is part of the text…
+        let books = read("\\id LUK\n\\c 1\n\\v 1 ὁ |κς| εἶπεν αὐτοῖς").unwrap();
+        assert_eq!(books[0].verse(1, 1).unwrap().text, "ὁ |κς| εἶπεν αὐτοῖς");
+
+        // …and inside one, up to its attributes.
+        let s = "\\id LUK\n\\c 1\n\\v 1 ὁ \\w |κς||lemma=\"κύριος\"\\w* εἶπεν";
+        let books = read(s).unwrap();
+        assert_eq!(books[0].verse(1, 1).unwrap().text, "ὁ |κς| εἶπεν");
+    }
+
+    #[test]
+    fn test_write() {
+        let books = read(S).unwrap();
+        let usfm = write(&books).unwrap();
+
+        let again = read(&usfm).unwrap();
+        assert_eq!(again.len(), books.len());
+        for (a, b) in again.iter().zip(&books) {
+            let a : Vec<_> = a.verses().map(|(c, v)| (c, v.number, v.last, &v.text)).collect();
+            let b : Vec<_> = b.verses().map(|(c, v)| (c, v.number, v.last, &v.text)).collect();
+            assert_eq!(a, b);
+        }
+        assert!(usfm.starts_with("\\id MAT\n\\c 1\n\\p\n\\v 1 Βίβλος γενέσεως"));
+        assert!(write_core(&books).unwrap()
+                                  .ends_with("\\id MRK\n\\c 1\n\\p\n\
+                                              \\v 1 αρχητουευαγγελιου\n"));
+    }
+}
@@ -0,0 +1,269 @@
+//! The kinds of the variation units found between two texts.
+//!
+//! Each [`Hunk`] of a diff is a variation unit, labelled as an omission,
+//! an addition, a substitution or a transposition of the second text
+//! against the first one. The units whose texts are the same under a
+//! looser [`CoreProfile`] are flagged as orthographic.
+//! # Example :
+//! ```
+//! use gnt_tools::CoreProfile;
+//! use gnt_tools::diff::{diff_words, words_with};
+//! use gnt_tools::variation::{classify, VariationKind};
+//!
+//! // Iota subscripts make a difference…
+//! let strict = CoreProfile::new().keep_iota_subscript(true);
+//! let a = words_with("λέγων ἐν ἑαυτῷ· τί ποιήσω", &strict).unwrap();
+//! let b = words_with("ἐν ἑαυτω τί ποιήσω λέγων", &strict).unwrap();
+//!
+//! // …but not under the profile of `core_char`.
+//! let units = classify(diff_words(&a, &b), &CoreProfile::new()).unwrap();
+//! let kinds : Vec<_> = units.iter().map(|u| (u.kind, u.orthographic)).collect();
+//!
+//! assert_eq!(kinds, [
+//!     (VariationKind::Transposition, false),
+//!     (VariationKind::Substitution, true),
+//!     (VariationKind::Transposition, false),
+//! ]);
+//! assert_eq!(units[0].moved, Some(2));
+//! ```
+
+use crate::diff::{Hunk, HunkKind};
+use crate::{CoreProfile, UnhandledGreekChar};
+
+/// The largest number of units between an omission and an addition paired
+/// as a transposition, when the verses of the units are not known.
+pub const MOVE_WINDOW: usize = 8;
+
+/// The kind of a variation unit, for the second text against the first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VariationKind {
+    /// Words of the first text missing in the second.
+    Omission,
+    /// Words of the second text missing in the first.
+    Addition,
+    /// Words of the first text replaced by other words in the second.
+    Substitution,
+    /// Words found in both texts, but not at the same place.
+    Transposition,
+}
+
+/// A labelled variation unit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariationUnit {
+    /// The words of each text.
+    pub hunk: Hunk,
+    /// The kind of the unit.
+    pub kind: VariationKind,
+    /// Whether the texts of the unit are the same under the looser
+    /// profile.
+    pub orthographic: bool,
+    /// For the words moved away from their place, the index of the unit
+    /// of their other place : the omission of the first text is paired
+    /// with the addition of the second. `None` for the words transposed
+    /// within the unit, e.g. `δε ο` for `ο δε`.
+    pub moved: Option<usize>,
+}
+
+/// Labels each of the `hunks` of a diff.
+///
+/// A transposition is either a unit whose two texts have the same words
+/// in another order, or an omission and an addition of the same words in
+/// the same verse. Without the verses of the units, the omission and the
+/// addition must be at most [`MOVE_WINDOW`] units apart. Of several such
+/// additions, the nearest one is paired.
+///
+/// The core texts of the units are compared again with `looser`, to flag
+/// the orthographic differences and to find the same words.
+pub fn classify(hunks: Vec<Hunk>, looser: &CoreProfile)
+    -> Result<Vec<VariationUnit>, UnhandledGreekChar> {
+
+    let mut units = Vec::with_capacity(hunks.len());
+    for hunk in hunks {
+        let orthographic = looser.core_text(&hunk.a.text)?.as_str()
+                           == looser.core_text(&hunk.b.text)?.as_str();
+        let kind = match hunk.kind {
+            HunkKind::Delete => VariationKind::Omission,
+            HunkKind::Insert => VariationKind::Addition,
+            HunkKind::Replace if !orthographic
+                                 && same_words(&hunk, looser)? => {
+                VariationKind::Transposition
+            }
+            HunkKind::Replace => VariationKind::Substitution,
+        };
+        units.push(VariationUnit { hunk, kind, orthographic, moved: None });
+    }
+
+    // Pair the omissions and additions of the same words.
+    for i in 0..units.len() {
+        if units[i].kind != VariationKind::Omission {
+            continue;
+        }
+        let words = core(&units[i].hunk.a.text, looser)?;
+        let mut others : Vec<usize> = (0..units.len()).collect();
+        others.sort_by_key(|&j| j.abs_diff(i));
+        for j in others {
+            if units[j].kind == VariationKind::Addition
+               && near(&units[i].hunk, &units[j].hunk, i.abs_diff(j))
+               && core(&units[j].hunk.b.text, looser)? == words {
+                units[i].kind = VariationKind::Transposition;
+                units[j].kind = VariationKind::Transposition;
+                units[i].moved = Some(j);
+                units[j].moved = Some(i);
+                break;
+            }
+        }
+    }
+
+    Ok(units)
+}
+
+/// Whether the units of `a` and `b`, `distance` units apart, are close
+/// enough for their words to be a transposition.
+fn near(a: &Hunk, b: &Hunk, distance: usize) -> bool {
+    match (a.a.reference, b.a.reference) {
+        (Some(ra), Some(rb)) => ra == rb,
+        _ => distance <= MOVE_WINDOW,
+    }
+}
+
+/// Whether the two texts of `hunk` have the same words under `profile`.
+fn same_words(hunk: &Hunk, profile: &CoreProfile)
+    -> Result<bool, UnhandledGreekChar> {
+    let words = |text: &str| -> Result<Vec<String>, UnhandledGreekChar> {
+        let mut words = text.split(' ')
+                            .map(|word| core(word, profile))
+                            .collect::<Result<Vec<_>, _>>()?;
+        words.sort();
+        Ok(words)
+    };
+
+    Ok(words(&hunk.a.text)? == words(&hunk.b.text)?)
+}
+
+fn core(text: &str, profile: &CoreProfile)
+    -> Result<String, UnhandledGreekChar> {
+    Ok(profile.core_text(text)?.into_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{diff, diff_words, words_with, Side};
+
+    fn kinds(a: &str, b: &str) -> Vec<(VariationKind, bool, Option<usize>)> {
+        classify(diff(a, b).unwrap(), &CoreProfile::new())
+            .unwrap()
+            .iter()
+            .map(|u| (u.kind, u.orthographic, u.moved))
+            .collect()
+    }
+
+    #[test]
+    fn test_classify() {
+        use VariationKind::*;
+
+        assert_eq!(kinds("εἶπεν δὲ αὐτοῖς", "εἶπεν αὐτοῖς"),
+                   [(Omission, false, None)]);
+        assert_eq!(kinds("εἶπεν αὐτοῖς", "εἶπεν δὲ αὐτοῖς"),
+                   [(Addition, false, None)]);
+        assert_eq!(kinds("εἶπεν δὲ αὐτοῖς", "ἔλεγεν δὲ αὐτοῖς"),
+                   [(Substitution, false, None)]);
+        assert_eq!(kinds("ὁ δὲ Ἰησοῦς εἶπεν", "δὲ ὁ Ἰησοῦς εἶπεν"),
+                   [(Transposition, false, Some(1)),
+                    (Transposition, false, Some(0))]);
+        assert_eq!(kinds("ὁ Ἰησοῦς εἶπεν αὐτοῖς πάλιν",
+                         "εἶπεν αὐτοῖς πάλιν ὁ Ἰησοῦς"),
+                   [(Transposition, false, Some(1)),
+                    (Transposition, false, Some(0))]);
+
+        // Words transposed within a unit.
+        let side = |words, text: &str| Side {
+            words,
+            span: 0..text.len(),
+            text: text.to_string(),
+            reference: None,
+        };
+        let hunk = Hunk {
+            kind: HunkKind::Replace,
+            a: side(0..2, "ὁ δὲ"),
+            b: side(0..2, "δὲ ὁ"),
+        };
+        let units = classify(vec![hunk], &CoreProfile::new()).unwrap();
+        assert_eq!(units[0].kind, Transposition);
+        assert_eq!(units[0].moved, None);
+    }
+
+    #[test]
+    fn test_far_apart() {
+        use crate::reference::{Book, Reference};
+        use VariationKind::*;
+
+        // The same word omitted and added too far away.
+        let a = format!("δὲ ὁ {}", "λόγος ἦν ".repeat(12));
+        let b = format!("ὁ {}δὲ", "λογοσ ἦν ".repeat(12));
+        let strict = CoreProfile::new().keep_final_sigma(true);
+        let units = classify(diff_words(&words_with(&a, &strict).unwrap(),
+                                        &words_with(&b, &strict).unwrap()),
+                             &strict).unwrap();
+        assert!(units.len() > MOVE_WINDOW + 1);
+        assert_eq!(units.first().unwrap().kind, Omission);
+        assert_eq!(units.last().unwrap().kind, Addition);
+
+        // In other verses.
+        let side = |words, text: &str, verse| Side {
+            words,
+            span: 0..text.len(),
+            text: text.to_string(),
+            reference: Some(Reference::new(Book::Luke, 12, verse)),
+        };
+        let omission = Hunk {
+            kind: HunkKind::Delete,
+            a: side(0..1, "δὲ", 16),
+            b: side(0..0, "", 16),
+        };
+        let addition = Hunk {
+            kind: HunkKind::Insert,
+            a: side(5..5, "", 17),
+            b: side(4..5, "δὲ", 17),
+        };
+        let units = classify(vec![omission.clone(), addition.clone()],
+                             &CoreProfile::new()).unwrap();
+        assert_eq!((units[0].kind, units[1].kind), (Omission, Addition));
+
+        let addition = Hunk { a: side(5..5, "", 16), ..addition };
+        let units = classify(vec![omission, addition],
+                             &CoreProfile::new()).unwrap();
+        assert_eq!((units[0].kind, units[1].kind),
+                   (Transposition, Transposition));
+    }
+
+    #[test]
+    fn test_looser_words() {
+        // Transposed words differing only in their final sigma.
+        let strict = CoreProfile::new().keep_final_sigma(true);
+        let a = words_with("ὁ λόγος ἦν πρὸς τὸν θεόν", &strict).unwrap();
+        let b = words_with("ἦν πρὸς τὸν θεόν ὁ λογοσ", &strict).unwrap();
+
+        let units = classify(diff_words(&a, &b), &CoreProfile::new()).unwrap();
+        assert_eq!(units.len(), 2);
+        assert!(units.iter().all(|u| u.kind == VariationKind::Transposition));
+
+        let units = classify(diff_words(&a, &b), &strict).unwrap();
+        assert_eq!(units[0].kind, VariationKind::Omission);
+    }
+
+    #[test]
+    fn test_orthographic() {
+        let strict = CoreProfile::new().keep_final_sigma(true);
+        let a = words_with("ὁ λόγος ἦν", &strict).unwrap();
+        let b = words_with("ὁ λογοσ ἦν", &strict).unwrap();
+
+        let units = classify(diff_words(&a, &b), &CoreProfile::new()).unwrap();
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].kind, VariationKind::Substitution);
+        assert!(units[0].orthographic);
+
+        let units = classify(diff_words(&a, &b), &strict).unwrap();
+        assert!(!units[0].orthographic);
+    }
+}
@@ -0,0 +1,381 @@
+//! The verse structure of a text where each verse starts with its number,
+//! and each chapter but the first with a header line, e.g. :
+//!
+//! ```text
+//! 1 Βίβλος γενέσεως Ἰησοῦ Χριστοῦ … 2 Ἀβραὰμ ἐγέννησεν τὸν Ἰσαάκ …
+//!
+//! ΚΑΤΑ ΜΑΘΘΑΙΟΝ  2
+//! 1 Τοῦ δὲ Ἰησοῦ γεννηθέντος ἐν Βηθλέεμ …
+//! ```
+//!
+//! [`core_text`](crate::core_text) drops the numbers, and the verse
+//! structure goes with them. [`parse`] keeps it, so that the core text can
+//! be given verse by verse.
+//! # Example :
+//! ```
+//! use gnt_tools::verse::parse;
+//!
+//! let s = "1 Βίβλος γενέσεως Ἰησοῦ Χριστοῦ 2 Ἀβραὰμ ἐγέννησεν τὸν Ἰσαάκ
+//!
+//!          ΚΑΤΑ ΜΑΘΘΑΙΟΝ  2
+//!          1 Τοῦ δὲ Ἰησοῦ γεννηθέντος ἐν Βηθλέεμ";
+//!
+//! let books = parse(s);
+//! let verse = books[0].verse(2, 1).unwrap();
+//!
+//! assert_eq!(books[0].title, "ΚΑΤΑ ΜΑΘΘΑΙΟΝ");
+//! assert_eq!(verse.text, "Τοῦ δὲ Ἰησοῦ γεννηθέντος ἐν Βηθλέεμ");
+//! assert_eq!(verse.core_text().unwrap(), "τουδειηϲουγεννηθεντοϲενβηθλεεμ");
+//! ```
+
+use std::ops::Range;
+
+use crate::reference::Book;
+use crate::{core_text, CoreText, UnhandledGreekChar};
+
+/// A book, as found in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookText {
+    /// The title of the book given by the chapter headers, e.g.
+    /// `ΚΑΤΑ ΜΑΘΘΑΙΟΝ`. Empty if the source has no header. A header line is
+    /// in capitals and its title is one of the names of a [`Book`].
+    pub title: String,
+    /// The chapters of the book, in the order of the source.
+    pub chapters: Vec<Chapter>,
+}
+
+/// A chapter of a [`BookText`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    /// The number of the chapter.
+    pub number: u32,
+    /// The verses of the chapter, in the order of the source.
+    pub verses: Vec<Verse>,
+}
+
+/// A verse of a [`Chapter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verse {
+    /// The number of the verse.
+    pub number: u32,
+    /// The number of the last verse of the text : the same as `number`,
+    /// unless the text bridges several verses, e.g. 3 for `2-3`.
+    pub last: u32,
+    /// The text of the verse, without its number nor the surrounding
+    /// whitespace.
+    pub text: String,
+    /// The byte range of the verse in the source, from the first letter
+    /// of `text` to the last one.
+    pub span: Range<usize>,
+    /// Where the pieces of `text` are in the source, when `text` is not
+    /// the range `span` of the source as it is, e.g. once the markup of an
+    /// OSIS or USFM file is left out : the position of each piece in
+    /// `text` and in the source, in order. Empty otherwise.
+    pub pieces: Vec<(usize, usize)>,
+}
+
+impl Verse {
+    /// The byte range in the source of the range `range` of
+    /// [`Verse::text`].
+    /// # Example :
+    /// ```
+    /// use gnt_tools::osis;
+    ///
+    /// let xml = r#"<osis><verse osisID="Luke.12.16">Εἶπεν <note>…</note>δὲ
+    ///              <hi>παραβολὴν</hi></verse></osis>"#;
+    /// let verse = osis::read(xml).unwrap()[0].verse(12, 16).unwrap().clone();
+    ///
+    /// assert_eq!(verse.text, "Εἶπεν δὲ παραβολὴν");
+    /// let word = verse.text.find("παραβολὴν").unwrap();
+    /// assert_eq!(&xml[verse.source_range(word..verse.text.len())], "παραβολὴν");
+    /// ```
+    pub fn source_range(&self, range: Range<usize>) -> Range<usize> {
+        let at = |i: usize, k: usize| match k {
+            0 => self.span.start + i,
+            k => {
+                let (text, source) = self.pieces[k - 1];
+                source + i - text
+            }
+        };
+        let start = self.pieces.partition_point(|(t, _)| *t <= range.start);
+        let end = self.pieces.partition_point(|(t, _)| *t < range.end);
+
+        at(range.start, start)..at(range.end, end)
+    }
+
+    /// The core text of the verse. Its spans are relative to
+    /// [`Verse::text`], see [`Verse::source_range`] for the position in the
+    /// source.
+    pub fn core_text(&self) -> Result<CoreText, UnhandledGreekChar> {
+        core_text(&self.text)
+    }
+}
+
+impl BookText {
+    /// The book whose title is [`BookText::title`], if it is one of the
+    /// New Testament.
+    pub fn book(&self) -> Option<Book> {
+        self.title.parse().ok()
+    }
+
+    /// The verse `verse` of the chapter `chapter`, if the book has it.
+    pub fn verse(&self, chapter: u32, verse: u32) -> Option<&Verse> {
+        self.chapters.iter()
+                     .filter(|c| c.number == chapter)
+                     .flat_map(|c| &c.verses)
+                     .find(|v| v.number == verse)
+    }
+
+    /// The verses of the book with their chapter number, in the order of
+    /// the source.
+    pub fn verses(&self) -> impl Iterator<Item = (u32, &Verse)> + '_ {
+        self.chapters.iter()
+                     .flat_map(|c| c.verses.iter().map(move |v| (c.number, v)))
+    }
+}
+
+/// Splits `s` into books, chapters and verses.
+///
+/// A header is a line of uppercase words, possibly followed by the number
+/// of the chapter it starts (`1` when there is none). A header with a new
+/// title starts a new book; the text before the first header is the first
+/// chapter of the book of this header.
+///
+/// Every number standing alone between whitespace starts a verse. Text
+/// found at the start of a chapter before any number is its verse `1`,
+/// since many editions do not print it.
+pub fn parse(s: &str) -> Vec<BookText> {
+    let mut books = vec![BookText { title: String::new(), chapters: Vec::new() }];
+    let mut chapter = Chapter { number: 1, verses: Vec::new() };
+    let mut line_start = 0;
+
+    for line in s.split_inclusive('\n') {
+        match header(line) {
+            Some((title, number)) => {
+                let book = books.last_mut().unwrap();
+                if !chapter.verses.is_empty() {
+                    book.chapters.push(chapter);
+                }
+                if book.title.is_empty() {
+                    book.title = title.to_string();
+                } else if book.title != title {
+                    books.push(BookText {
+                        title: title.to_string(),
+                        chapters: Vec::new(),
+                    });
+                }
+                chapter = Chapter { number, verses: Vec::new() };
+            }
+            None => verses(line, line_start, &mut chapter.verses),
+        }
+        line_start += line.len();
+    }
+
+    let book = books.last_mut().unwrap();
+    if !chapter.verses.is_empty() {
+        book.chapters.push(chapter);
+    }
+    if books.len() > 1 && books[0].chapters.is_empty() {
+        books.remove(0);
+    }
+    for verse in books.iter_mut()
+                      .flat_map(|b| &mut b.chapters)
+                      .flat_map(|c| &mut c.verses) {
+        verse.text = s[verse.span.clone()].to_string();
+    }
+
+    books
+}
+
+/// Adds `verse`, of the chapter `chapter` of `book`, at the end of
+/// `books`, starting a new book or chapter if needed. The title of a new
+/// book is its Greek title.
+pub(crate) fn push_verse(books: &mut Vec<BookText>, book: Book, chapter: u32,
+                         verse: Verse) {
+    if books.last().is_none_or(|b| b.book() != Some(book)) {
+        books.push(BookText {
+            title: book.greek_title().to_string(),
+            chapters: Vec::new(),
+        });
+    }
+    let chapters = &mut books.last_mut().unwrap().chapters;
+    if chapters.last().is_none_or(|c| c.number != chapter) {
+        chapters.push(Chapter { number: chapter, verses: Vec::new() });
+    }
+    chapters.last_mut().unwrap().verses.push(verse);
+}
+
+/// Adds the words of `s` to the text of `verse`, with a single space
+/// between them. `s` is read from `raw`, found at `start` in the source :
+/// the same text, or the one it is decoded from, e.g. with XML entities.
+/// A word not found as it is in `raw` is placed where the previous one
+/// ends.
+///
+/// The text may end with a space, to be trimmed once the verse is read.
+pub(crate) fn push_text(verse: &mut Verse, s: &str, raw: &str, start: usize) {
+    let mut rest = s;
+    // The position in `raw` after the last word found.
+    let mut cursor = 0;
+    loop {
+        let word = rest.trim_start();
+        if word.is_empty() {
+            break;
+        }
+        let offset = s.len() - word.len();
+        let len = word.find(char::is_whitespace).unwrap_or(word.len());
+        if offset > 0 && !verse.text.is_empty() && !verse.text.ends_with(' ') {
+            verse.text.push(' ');
+        }
+
+        let (at_raw, end_raw) = match raw[cursor..].find(&word[..len]) {
+            Some(k) => (cursor + k, cursor + k + len),
+            None => {
+                let end = raw[cursor..].trim_start();
+                let end = raw.len() - end.len()
+                          + end.find(char::is_whitespace).unwrap_or(end.len());
+                (cursor, end)
+            }
+        };
+        cursor = end_raw;
+        if verse.text.is_empty() {
+            verse.span.start = start + at_raw;
+        }
+        let at = verse.text.len();
+        if verse.source_range(at..at).start != start + at_raw {
+            verse.pieces.push((at, start + at_raw));
+        }
+        verse.text.push_str(&word[..len]);
+        verse.span.end = start + end_raw;
+        rest = &word[len..];
+    }
+    if s.ends_with(char::is_whitespace) && !verse.text.is_empty()
+       && !verse.text.ends_with(' ') {
+        verse.text.push(' ');
+    }
+}
+
+/// The title and chapter number of `line`, if it is a header.
+fn header(line: &str) -> Option<(&str, u32)> {
+    let line = line.trim();
+    let (title, number) = match line.rsplit_once(char::is_whitespace) {
+        Some((title, n)) if n.bytes().all(|b| b.is_ascii_digit()) => {
+            (title.trim_end(), n.parse().ok()?)
+        }
+        _ => (line, 1),
+    };
+
+    // A verse may be written in capitals too, but is not the title of a
+    // book.
+    let is_title = title.chars().any(char::is_alphabetic)
+                   && title.chars().all(|c| c.is_uppercase()
+                                            || c.is_whitespace())
+                   && title.parse::<Book>().is_ok();
+    is_title.then_some((title, number))
+}
+
+/// Adds the verses of `line`, found at `offset` in the source, to
+/// `verses`, without their text. A verse running on from the previous line
+/// is extended.
+fn verses(line: &str, offset: usize, verses: &mut Vec<Verse>) {
+    let mut start = None;
+
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        let word = match (start, c.is_whitespace()) {
+            (None, false) => {
+                start = Some(i);
+                continue;
+            }
+            (Some(s), true) => s..i,
+            _ => continue,
+        };
+        start = None;
+
+        let span = offset + word.start..offset + word.end;
+        let word = &line[word];
+        let number = Some(word).filter(|w| w.bytes().all(|b| b.is_ascii_digit()))
+                               .and_then(|w| w.parse().ok());
+        match (number, verses.last_mut()) {
+            (Some(number), _) => verses.push(Verse {
+                number,
+                last: number,
+                text: String::new(),
+                span: span.end..span.end,
+                pieces: Vec::new(),
+            }),
+            (None, Some(verse)) if verse.span.is_empty() => verse.span = span,
+            (None, Some(verse)) => verse.span.end = span.end,
+            (None, None) => verses.push(Verse {
+                number: 1,
+                last: 1,
+                text: String::new(),
+                span,
+                pieces: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const S: &str = "1 Βίβλος γενέσεως Ἰησοῦ Χριστοῦ υἱοῦ Δαυὶδ υἱοῦ Ἀβραάμ. 2 Ἀβραὰμ
+ἐγέννησεν τὸν Ἰσαάκ,
+
+ΚΑΤΑ ΜΑΘΘΑΙΟΝ  2
+1 Τοῦ δὲ Ἰησοῦ γεννηθέντος 2 λέγοντες· ποῦ ἐστιν
+
+ΚΑΤΑ ΜΑΡΚΟΝ  1
+Ἀρχὴ τοῦ εὐαγγελίου Ἰησοῦ Χριστοῦ. 2 Καθὼς γέγραπται
+";
+
+    #[test]
+    fn test_parse() {
+        let books = parse(S);
+
+        assert_eq!(books.len(), 2);
+        assert_eq!(books[0].title, "ΚΑΤΑ ΜΑΘΘΑΙΟΝ");
+        assert_eq!(books[1].title, "ΚΑΤΑ ΜΑΡΚΟΝ");
+        assert_eq!(books[1].book(), Some(Book::Mark));
+
+        let refs : Vec<_> = books[0].verses().map(|(c, v)| (c, v.number)).collect();
+        assert_eq!(refs, [(1, 1), (1, 2), (2, 1), (2, 2)]);
+
+        let verse = books[0].verse(1, 2).unwrap();
+        assert_eq!(verse.text, "Ἀβραὰμ\nἐγέννησεν τὸν Ἰσαάκ,");
+        assert_eq!(&S[verse.span.clone()], verse.text);
+
+        // A chapter starting without number.
+        let verse = books[1].verse(1, 1).unwrap();
+        assert_eq!(&S[verse.span.clone()], "Ἀρχὴ τοῦ εὐαγγελίου Ἰησοῦ Χριστοῦ.");
+        assert_eq!(books[1].verse(1, 2).unwrap().core_text().unwrap(),
+                   "καθωϲγεγραπται");
+    }
+
+    #[test]
+    fn test_capital_verse() {
+        // A verse line written in capitals, as an inscription.
+        let s = "37 καὶ ἐπέθηκαν ἐπάνω τῆς κεφαλῆς αὐτοῦ
+                 ΟΥΤΟΣ ΕΣΤΙΝ ΙΗΣΟΥΣ Ο ΒΑΣΙΛΕΥΣ ΤΩΝ ΙΟΥΔΑΙΩΝ
+                 38 Τότε σταυροῦνται σὺν αὐτῷ δύο λῃσταί";
+        let books = parse(s);
+
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0].title, "");
+        let refs : Vec<_> = books[0].verses().map(|(c, v)| (c, v.number)).collect();
+        assert_eq!(refs, [(1, 37), (1, 38)]);
+        assert!(books[0].verse(1, 37).unwrap().text.ends_with("ΤΩΝ ΙΟΥΔΑΙΩΝ"));
+    }
+
+    #[test]
+    fn test_header() {
+        assert_eq!(header("ΚΑΤΑ ΜΑΘΘΑΙΟΝ  2\n"), Some(("ΚΑΤΑ ΜΑΘΘΑΙΟΝ", 2)));
+        assert_eq!(header("ΠΡΟΣ ΡΩΜΑΙΟΥΣ"), Some(("ΠΡΟΣ ΡΩΜΑΙΟΥΣ", 1)));
+        assert_eq!(header("2 Ἀβραὰμ"), None);
+        assert_eq!(header("ΙΗΣΟΥΣ εἶπεν 3"), None);
+        assert_eq!(header("ΚΑΤΑ ΛΟΥΚΑΝ"), Some(("ΚΑΤΑ ΛΟΥΚΑΝ", 1)));
+        assert_eq!(header("ΑΓΙΟΣ ΑΓΙΟΣ ΑΓΙΟΣ"), None);
+        assert_eq!(header("ΚΑΤΑ ΘΩΜΑΝ  2"), None);
+        assert_eq!(header(""), None);
+    }
+}
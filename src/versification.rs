@@ -0,0 +1,297 @@
+//! The verse divisions of the editions, and the mapping between them.
+//!
+//! Editions do not always number the same text the same way : the last
+//! words of Rev 12 are 12:18 in NA but belong to 13:1 in the KJV, the
+//! doxology of Rom 16:25-27 is Rom 14:24-26 in the Byzantine text, and
+//! the critical texts have no Mt 17:21. [`align`] puts together the verses
+//! of two editions holding the same passage, so that their core texts can
+//! be compared whatever their numbering.
+//! # Example :
+//! ```
+//! use gnt_tools::reference::Reference;
+//! use gnt_tools::versification::Versification;
+//!
+//! let kjv : Reference = "3 John 14".parse().unwrap();
+//!
+//! assert_eq!(Versification::Kjv.map(kjv, Versification::Critical),
+//!            ["3 John 14".parse().unwrap(), "3 John 15".parse().unwrap()]);
+//!
+//! let omitted : Reference = "Mt 17:21".parse().unwrap();
+//!
+//! assert!(Versification::Kjv.map(omitted, Versification::Critical).is_empty());
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::reference::{Book, Reference};
+use crate::reference::Book::*;
+use crate::verse::{BookText, Verse};
+
+/// A verse numbering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Versification {
+    /// The numbering of the critical editions : Nestle-Aland, UBS, SBLGNT.
+    Critical,
+    /// The numbering of the Byzantine text (Robinson-Pierpont).
+    Byzantine,
+    /// The numbering of the Textus Receptus, as in the KJV.
+    Kjv,
+}
+
+const fn r(book: Book, chapter: u32, verse: u32) -> Reference {
+    Reference::new(book, chapter, verse)
+}
+
+/// The verses that the critical editions do not have, in their
+/// traditional numbering.
+const CRITICAL_ABSENT: &[Reference] = &[
+    r(Matthew, 17, 21), r(Matthew, 18, 11), r(Matthew, 23, 14),
+    r(Mark, 7, 16), r(Mark, 9, 44), r(Mark, 9, 46), r(Mark, 11, 26),
+    r(Mark, 15, 28), r(Luke, 17, 36), r(Luke, 23, 17), r(John, 5, 4),
+    r(Acts, 8, 37), r(Acts, 15, 34), r(Acts, 24, 7), r(Acts, 28, 29),
+    r(Romans, 16, 24),
+];
+
+/// The verses that the Byzantine text does not have.
+const BYZANTINE_ABSENT: &[Reference] = &[
+    r(Luke, 17, 36), r(Acts, 8, 37), r(Acts, 15, 34),
+];
+
+/// The verses of the Byzantine numbering that are numbered otherwise in
+/// the critical one.
+const BYZANTINE: &[(Reference, &[Reference])] = &[
+    (r(Romans, 14, 24), &[r(Romans, 16, 25)]),
+    (r(Romans, 14, 25), &[r(Romans, 16, 26)]),
+    (r(Romans, 14, 26), &[r(Romans, 16, 27)]),
+];
+
+/// The verses of the KJV numbering that are numbered otherwise in the
+/// critical one.
+const KJV: &[(Reference, &[Reference])] = &[
+    (r(SecondCorinthians, 13, 12), &[r(SecondCorinthians, 13, 12)]),
+    (r(SecondCorinthians, 13, 13), &[r(SecondCorinthians, 13, 12)]),
+    (r(SecondCorinthians, 13, 14), &[r(SecondCorinthians, 13, 13)]),
+    (r(Philippians, 1, 16), &[r(Philippians, 1, 17)]),
+    (r(Philippians, 1, 17), &[r(Philippians, 1, 16)]),
+    (r(ThirdJohn, 1, 14), &[r(ThirdJohn, 1, 14), r(ThirdJohn, 1, 15)]),
+    (r(Revelation, 13, 1), &[r(Revelation, 12, 18), r(Revelation, 13, 1)]),
+];
+
+impl Versification {
+    fn rules(self) -> &'static [(Reference, &'static [Reference])] {
+        match self {
+            Versification::Critical => &[],
+            Versification::Byzantine => BYZANTINE,
+            Versification::Kjv => KJV,
+        }
+    }
+
+    fn absent(self) -> &'static [Reference] {
+        match self {
+            Versification::Critical => CRITICAL_ABSENT,
+            Versification::Byzantine => BYZANTINE_ABSENT,
+            Versification::Kjv => &[],
+        }
+    }
+
+    /// The verses of the critical numbering holding the text of the verse
+    /// `r` of this numbering. The verses absent from the critical editions
+    /// keep their traditional number.
+    pub fn critical(self, r: Reference) -> Vec<Reference> {
+        match self.rules().iter().find(|(own, _)| *own == r) {
+            Some((_, critical)) => critical.to_vec(),
+            None => vec![r],
+        }
+    }
+
+    /// The verses of the numbering `to` holding the text of the verse `r`
+    /// of this numbering, if `to` has it.
+    ///
+    /// Only the differences listed in this module are known; the other
+    /// verses are taken to have the same number in every edition.
+    pub fn map(self, r: Reference, to: Versification) -> Vec<Reference> {
+        if self.absent().contains(&r) {
+            return Vec::new();
+        }
+
+        let mut mapped = Vec::new();
+        for c in self.critical(r) {
+            if to.absent().contains(&c) {
+                continue;
+            }
+            let own = to.rules()
+                        .iter()
+                        .filter(|(_, critical)| critical.contains(&c))
+                        .map(|(own, _)| *own);
+            let before = mapped.len();
+            mapped.extend(own);
+            if mapped.len() == before {
+                mapped.push(c);
+            }
+        }
+
+        mapped.sort();
+        mapped.dedup();
+        mapped
+    }
+}
+
+/// Verses of two editions holding the same passage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlignedVerses<'a> {
+    /// The passage, in the critical numbering (see
+    /// [`Versification::critical`]).
+    pub critical: Vec<Reference>,
+    /// The verses of the first edition, with their chapter number.
+    pub a: Vec<(u32, &'a Verse)>,
+    /// The verses of the second edition, with their chapter number.
+    pub b: Vec<(u32, &'a Verse)>,
+}
+
+/// Puts together the verses of `a`, numbered following `a_numbering`, and
+/// of `b`, numbered following `b_numbering`, that hold the same passage of
+/// `book`. The passages are in the order of the critical numbering; a
+/// passage only one edition has is given with no verse for the other.
+/// # Example :
+/// ```
+/// use gnt_tools::reference::Book;
+/// use gnt_tools::verse::parse;
+/// use gnt_tools::versification::{align, Versification};
+///
+/// let na = parse("14 ἐλπίζω δὲ εὐθέως σε ἰδεῖν 15 εἰρήνη σοι.");
+/// let tr = parse("14 ἐλπίζω δὲ εὐθέως ἰδεῖν σε εἰρήνη σοι.");
+///
+/// let aligned = align(Book::ThirdJohn, &na[0], Versification::Critical,
+///                     &tr[0], Versification::Kjv);
+///
+/// assert_eq!(aligned.len(), 1);
+/// assert_eq!(aligned[0].a.len(), 2);
+/// ```
+pub fn align<'a>(book: Book,
+                 a: &'a BookText, a_numbering: Versification,
+                 b: &'a BookText, b_numbering: Versification)
+    -> Vec<AlignedVerses<'a>> {
+
+    let mut groups : Vec<AlignedVerses<'a>> = Vec::new();
+    // The group of each verse of the critical numbering met so far.
+    let mut group_of : BTreeMap<Reference, usize> = BTreeMap::new();
+
+    let verses = a.verses().map(|v| (v, a_numbering, true))
+                  .chain(b.verses().map(|v| (v, b_numbering, false)));
+
+    for ((chapter, verse), numbering, in_a) in verses {
+        let critical = numbering.critical(Reference::new(book, chapter,
+                                                         verse.number));
+        let mut found : Vec<usize> = critical.iter()
+                                             .filter_map(|c| group_of.get(c))
+                                             .copied()
+                                             .collect();
+        found.sort();
+        found.dedup();
+
+        let g = match found.first() {
+            Some(g) => *g,
+            None => {
+                groups.push(AlignedVerses {
+                    critical: Vec::new(),
+                    a: Vec::new(),
+                    b: Vec::new(),
+                });
+                groups.len() - 1
+            }
+        };
+        // Merge the other groups holding part of the passage into `g`.
+        for other in found.iter().skip(1).rev() {
+            let merged = std::mem::replace(&mut groups[*other], AlignedVerses {
+                critical: Vec::new(),
+                a: Vec::new(),
+                b: Vec::new(),
+            });
+            for c in &merged.critical {
+                group_of.insert(*c, g);
+            }
+            groups[g].critical.extend(merged.critical);
+            groups[g].a.extend(merged.a);
+            groups[g].b.extend(merged.b);
+        }
+
+        for c in critical {
+            if group_of.insert(c, g).is_none() {
+                groups[g].critical.push(c);
+            }
+        }
+        if in_a {
+            groups[g].a.push((chapter, verse));
+        } else {
+            groups[g].b.push((chapter, verse));
+        }
+    }
+
+    groups.retain(|g| !g.critical.is_empty());
+    for g in &mut groups {
+        g.critical.sort();
+        g.a.sort_by_key(|(c, v)| (*c, v.number));
+        g.b.sort_by_key(|(c, v)| (*c, v.number));
+    }
+    groups.sort_by_key(|g| g.critical[0]);
+
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verse::parse;
+
+    fn refs(s: &[&str]) -> Vec<Reference> {
+        s.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_map() {
+        use Versification::*;
+
+        let rev = refs(&["Rev 12:18", "Rev 13:1"]);
+        assert_eq!(Critical.map(rev[0], Kjv), refs(&["Rev 13:1"]));
+        assert_eq!(Kjv.map(rev[1], Critical), rev);
+        assert_eq!(Kjv.map(rev[1], Byzantine), rev);
+
+        let doxology = refs(&["Rom 16:25", "Rom 14:24"]);
+        assert_eq!(Kjv.map(doxology[0], Byzantine), [doxology[1]]);
+        assert_eq!(Byzantine.map(doxology[1], Critical), [doxology[0]]);
+
+        // Omitted verses.
+        let mt = refs(&["Mt 17:21"]);
+        assert_eq!(Kjv.map(mt[0], Byzantine), mt);
+        assert!(Kjv.map(mt[0], Critical).is_empty());
+        assert!(Critical.map(mt[0], Kjv).is_empty());
+        assert!(Kjv.map(refs(&["Acts 8:37"])[0], Byzantine).is_empty());
+
+        let same = refs(&["Lk 12:16"]);
+        assert_eq!(Kjv.map(same[0], Byzantine), same);
+    }
+
+    #[test]
+    fn test_align() {
+        let na = parse("ΠΡΟΣ ΚΟΡΙΝΘΙΟΥΣ Β  13\n11 λοιπόν, ἀδελφοί, χαίρετε 12 ἀσπάσασθε ἀλλήλους \
+                        ἐν ἁγίῳ φιλήματι. ἀσπάζονται ὑμᾶς οἱ ἅγιοι πάντες. \
+                        13 ἡ χάρις");
+        let tr = parse("ΠΡΟΣ ΚΟΡΙΝΘΙΟΥΣ Β  13\n12 ἀσπάσασθε ἀλλήλους ἐν ἁγίῳ φιλήματι. \
+                        13 ἀσπάζονται ὑμᾶς οἱ ἅγιοι πάντες. 14 ἡ χάρις");
+
+        let book = na[0].book().unwrap();
+        let aligned = align(book, &na[0], Versification::Critical,
+                            &tr[0], Versification::Kjv);
+        let numbers : Vec<(Vec<u32>, Vec<u32>)> =
+            aligned.iter()
+                   .map(|g| (g.a.iter().map(|(_, v)| v.number).collect(),
+                             g.b.iter().map(|(_, v)| v.number).collect()))
+                   .collect();
+
+        assert_eq!(numbers, [
+            (vec![11], vec![]),
+            (vec![12], vec![12, 13]),
+            (vec![13], vec![14]),
+        ]);
+    }
+}